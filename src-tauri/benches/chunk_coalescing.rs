@@ -0,0 +1,141 @@
+// Replays a recorded chat stream through `ChunkCoalescer` and compares the number of
+// emitted events and the latency added to each delta for a few configurations.
+//
+// Run with `cargo bench --bench chunk_coalescing`.
+use pluely_lib::coalesce::{ChunkCoalescer, CoalesceConfig};
+use std::time::{Duration, Instant};
+
+const RECORDED_STREAM: &str = include_str!("fixtures/chat_stream.sse");
+const OVERHEAD_ITERATIONS: u32 = 500;
+
+struct Outcome {
+    events: usize,
+    mean_latency: Duration,
+    p95_latency: Duration,
+    max_latency: Duration,
+}
+
+// (offset from request start, delta text)
+fn load_deltas() -> Vec<(Duration, String)> {
+    RECORDED_STREAM
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+        .filter_map(|line| {
+            let (offset, raw) = line.split_once('\t')?;
+            let json = raw.strip_prefix("data: ")?;
+            let parsed: serde_json::Value = serde_json::from_str(json).ok()?;
+            let content = parsed["choices"][0]["delta"]["content"].as_str()?;
+            Some((
+                Duration::from_millis(offset.parse().ok()?),
+                content.to_string(),
+            ))
+        })
+        .collect()
+}
+
+// Simulated clock: deltas arrive at their recorded offsets and the flush timer fires at
+// the coalescer deadline, exactly like the streaming loop in `api.rs`
+fn replay(deltas: &[(Duration, String)], config: CoalesceConfig) -> Outcome {
+    let start = Instant::now();
+    let mut coalescer = ChunkCoalescer::new(config);
+    let mut waiting: Vec<Instant> = Vec::new();
+    let mut latencies: Vec<Duration> = Vec::new();
+    let mut events = 0;
+
+    for (offset, delta) in deltas {
+        let now = start + *offset;
+
+        if let Some(deadline) = coalescer.deadline().filter(|deadline| *deadline < now) {
+            if coalescer.flush().is_some() {
+                events += 1;
+                latencies.extend(waiting.drain(..).map(|arrived| deadline - arrived));
+            }
+        }
+
+        waiting.push(now);
+        if coalescer.push(delta, now).is_some() {
+            events += 1;
+            latencies.extend(waiting.drain(..).map(|arrived| now - arrived));
+        }
+    }
+
+    // Stream completion flushes immediately
+    if let Some(&(offset, _)) = deltas.last() {
+        if coalescer.flush().is_some() {
+            events += 1;
+            let now = start + offset;
+            latencies.extend(waiting.drain(..).map(|arrived| now - arrived));
+        }
+    }
+
+    latencies.sort();
+    let total: Duration = latencies.iter().sum();
+    let mean_latency = total / latencies.len().max(1) as u32;
+    let p95_latency = latencies
+        .get(latencies.len() * 95 / 100)
+        .copied()
+        .unwrap_or_default();
+    let max_latency = latencies.last().copied().unwrap_or_default();
+
+    Outcome {
+        events,
+        mean_latency,
+        p95_latency,
+        max_latency,
+    }
+}
+
+// Real CPU cost of the coalescer per delta
+fn overhead_per_delta(deltas: &[(Duration, String)], config: CoalesceConfig) -> Duration {
+    let started = Instant::now();
+    for _ in 0..OVERHEAD_ITERATIONS {
+        std::hint::black_box(replay(std::hint::black_box(deltas), config));
+    }
+    started.elapsed() / (OVERHEAD_ITERATIONS * deltas.len() as u32)
+}
+
+fn main() {
+    let deltas = load_deltas();
+    let stream_duration = deltas
+        .first()
+        .zip(deltas.last())
+        .map(|(first, last)| last.0 - first.0)
+        .unwrap_or_default();
+
+    println!(
+        "Recorded stream: {} deltas over {:?}\n",
+        deltas.len(),
+        stream_duration
+    );
+    println!(
+        "{:<22} {:>7} {:>9} {:>10} {:>10} {:>10} {:>11}",
+        "config", "events", "events/s", "mean lat", "p95 lat", "max lat", "cpu/delta"
+    );
+
+    let configs = [
+        ("no batching", 0, 0),
+        ("16 ms / 16 chars", 16, 16),
+        ("50 ms / 32 chars", 50, 32),
+        ("100 ms / 64 chars", 100, 64),
+    ];
+
+    for (label, flush_interval_ms, min_chars) in configs {
+        let config = CoalesceConfig {
+            flush_interval_ms,
+            min_chars,
+        };
+        let outcome = replay(&deltas, config);
+        let events_per_sec = outcome.events as f64 / stream_duration.as_secs_f64().max(0.001);
+
+        println!(
+            "{:<22} {:>7} {:>9.1} {:>10.1?} {:>10.1?} {:>10.1?} {:>11.1?}",
+            label,
+            outcome.events,
+            events_per_sec,
+            outcome.mean_latency,
+            outcome.p95_latency,
+            outcome.max_latency,
+            overhead_per_delta(&deltas, config),
+        );
+    }
+}
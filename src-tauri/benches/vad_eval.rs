@@ -0,0 +1,260 @@
+// Scores each voice activity detector hop by hop against labelled recordings and prints
+// precision, recall and F1 per fixture and overall.
+//
+// Run with `cargo bench --bench vad_eval`. The neural detector is included when built
+// with `--features neural-vad` and `PLUELY_VAD_MODEL` points at a Silero VAD .onnx file.
+//
+// Fixtures live in `fixtures/vad`: a mono WAV plus an Audacity label export of the same
+// name (`<start secs>\t<end secs>\t<label>` per speech segment). The bundled ones are
+// synthetic scenes (voiced syllables with quiet rooms, fan noise, keyboard clicks and
+// music); drop real recordings next to them to evaluate on those too.
+use pluely_lib::vad::{apply_noise_gate, EnergyVad, GmmVad, NoiseFloorTracker, VoiceDetector};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FIXTURE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/benches/fixtures/vad");
+// Same hop duration as the capture default (1024 samples at 44.1 kHz)
+const HOP_MS: u32 = 23;
+// `VadConfig` default; hops are gated before detection like in the capture loop
+const NOISE_GATE_THRESHOLD: f32 = 0.003;
+
+struct Fixture {
+    name: String,
+    samples: Vec<f32>,
+    sample_rate: u32,
+    speech: Vec<(f32, f32)>,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Counts {
+    true_positive: u32,
+    false_positive: u32,
+    false_negative: u32,
+    true_negative: u32,
+}
+
+impl Counts {
+    fn add(&mut self, other: Counts) {
+        self.true_positive += other.true_positive;
+        self.false_positive += other.false_positive;
+        self.false_negative += other.false_negative;
+        self.true_negative += other.true_negative;
+    }
+
+    fn precision(&self) -> f64 {
+        ratio(self.true_positive, self.true_positive + self.false_positive)
+    }
+
+    fn recall(&self) -> f64 {
+        ratio(self.true_positive, self.true_positive + self.false_negative)
+    }
+
+    fn f1(&self) -> f64 {
+        let (p, r) = (self.precision(), self.recall());
+        if p + r == 0.0 {
+            0.0
+        } else {
+            2.0 * p * r / (p + r)
+        }
+    }
+
+    // Share of non-speech hops classified as speech
+    fn false_alarm_rate(&self) -> f64 {
+        ratio(
+            self.false_positive,
+            self.false_positive + self.true_negative,
+        )
+    }
+}
+
+fn ratio(numerator: u32, denominator: u32) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+fn load_wav(path: &Path) -> Result<(Vec<f32>, u32), String> {
+    let mut reader = hound::WavReader::open(path).map_err(|e| e.to_string())?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?,
+        hound::SampleFormat::Int => {
+            let scale = (1u64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()
+                .map_err(|e| e.to_string())?
+        }
+    };
+
+    // Downmix like the capture backends do
+    let mono = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+    Ok((mono, spec.sample_rate))
+}
+
+fn load_labels(path: &Path) -> Result<Vec<(f32, f32)>, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut fields = line.split('\t');
+            let start = fields.next().and_then(|s| s.trim().parse().ok());
+            let end = fields.next().and_then(|s| s.trim().parse().ok());
+            start
+                .zip(end)
+                .ok_or_else(|| format!("Bad label line: {}", line))
+        })
+        .collect()
+}
+
+fn load_fixtures() -> Vec<Fixture> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(FIXTURE_DIR)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "wav"));
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            let loaded = load_wav(&path)
+                .and_then(|wav| Ok((wav, load_labels(&path.with_extension("txt"))?)));
+            match loaded {
+                Ok(((samples, sample_rate), speech)) => Some(Fixture {
+                    name,
+                    samples,
+                    sample_rate,
+                    speech,
+                }),
+                Err(e) => {
+                    eprintln!("Skipping {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+// A hop counts as speech when its midpoint falls inside a labelled segment. Hops are
+// noise-gated, and the noise floor tracked and handed to the detector, like the capture
+// loop does.
+fn evaluate(fixture: &Fixture, detector: &mut dyn VoiceDetector) -> Counts {
+    let hop_size = (fixture.sample_rate * HOP_MS / 1000) as usize;
+    let mut counts = Counts::default();
+    let mut noise_floor = NoiseFloorTracker::new(fixture.sample_rate);
+
+    for (index, hop) in fixture.samples.chunks_exact(hop_size).enumerate() {
+        let midpoint = (index as f32 + 0.5) * hop_size as f32 / fixture.sample_rate as f32;
+        let labelled = fixture
+            .speech
+            .iter()
+            .any(|&(start, end)| midpoint >= start && midpoint < end);
+
+        let hop = apply_noise_gate(hop, NOISE_GATE_THRESHOLD);
+        noise_floor.update(&hop);
+        if let Some(floor) = noise_floor.floor_rms() {
+            detector.set_noise_floor(floor);
+        }
+
+        match (detector.is_speech(&hop), labelled) {
+            (true, true) => counts.true_positive += 1,
+            (true, false) => counts.false_positive += 1,
+            (false, true) => counts.false_negative += 1,
+            (false, false) => counts.true_negative += 1,
+        }
+    }
+
+    counts
+}
+
+type DetectorFactory = Box<dyn Fn(u32) -> Option<Box<dyn VoiceDetector>>>;
+
+fn detectors() -> Vec<(&'static str, DetectorFactory)> {
+    // Thresholds and margin from the `VadConfig` defaults
+    #[cfg_attr(not(feature = "neural-vad"), allow(unused_mut))]
+    let mut detectors: Vec<(&'static str, DetectorFactory)> = vec![
+        (
+            "energy",
+            Box::new(|_| Some(Box::new(EnergyVad::new(0.012, 0.035)) as Box<dyn VoiceDetector>)),
+        ),
+        (
+            "adaptive",
+            Box::new(|_| {
+                Some(Box::new(EnergyVad::new(0.012, 0.035).adaptive(3.0)) as Box<dyn VoiceDetector>)
+            }),
+        ),
+        (
+            "gmm",
+            Box::new(|rate| Some(Box::new(GmmVad::new(rate)) as Box<dyn VoiceDetector>)),
+        ),
+    ];
+
+    #[cfg(feature = "neural-vad")]
+    if let Ok(model) = std::env::var("PLUELY_VAD_MODEL") {
+        detectors.push((
+            "neural",
+            Box::new(move |rate| {
+                pluely_lib::vad::NeuralVad::new(Path::new(&model), rate)
+                    .map_err(|e| eprintln!("{}", e))
+                    .ok()
+                    .map(|vad| Box::new(vad) as Box<dyn VoiceDetector>)
+            }),
+        ));
+    }
+
+    detectors
+}
+
+fn main() {
+    let fixtures = load_fixtures();
+    if fixtures.is_empty() {
+        println!("No fixtures found in {}", FIXTURE_DIR);
+        return;
+    }
+
+    println!(
+        "{:<8} {:<22} {:>9} {:>7} {:>6} {:>11}",
+        "detector", "fixture", "precision", "recall", "F1", "false alarm"
+    );
+
+    for (label, factory) in detectors() {
+        let mut total = Counts::default();
+        for fixture in &fixtures {
+            let Some(mut detector) = factory(fixture.sample_rate) else {
+                continue;
+            };
+            let counts = evaluate(fixture, detector.as_mut());
+            total.add(counts);
+            println!(
+                "{:<8} {:<22} {:>9.3} {:>7.3} {:>6.3} {:>11.3}",
+                label,
+                fixture.name,
+                counts.precision(),
+                counts.recall(),
+                counts.f1(),
+                counts.false_alarm_rate(),
+            );
+        }
+        println!(
+            "{:<8} {:<22} {:>9.3} {:>7.3} {:>6.3} {:>11.3}\n",
+            label,
+            "overall",
+            total.precision(),
+            total.recall(),
+            total.f1(),
+            total.false_alarm_rate(),
+        );
+    }
+}
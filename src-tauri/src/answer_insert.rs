@@ -0,0 +1,336 @@
+// Inserts answers into the application the user was working in, either by pasting through
+// the clipboard or by simulating keystrokes
+use arboard::Clipboard;
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+const DEFAULT_CHARS_PER_SECOND: u32 = 40;
+const MAX_CHARS_PER_SECOND: u32 = 1000;
+// Time for focus to return to the target application after the overlay hides
+const FOCUS_SETTLE_MS: u64 = 150;
+// The target application reads the clipboard asynchronously after the paste keystroke
+const CLIPBOARD_RESTORE_MS: u64 = 300;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InsertMode {
+    // Put the text on the clipboard and send the paste shortcut
+    #[default]
+    Paste,
+    // Type the text character by character, for fields that block pasting
+    Type,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InsertConfig {
+    #[serde(default)]
+    pub mode: InsertMode,
+    #[serde(default = "default_chars_per_second")]
+    pub chars_per_second: u32,
+    // The shortcut inserts the last code block of the answer instead of the whole answer
+    #[serde(default)]
+    pub prefer_code_block: bool,
+}
+
+fn default_chars_per_second() -> u32 {
+    DEFAULT_CHARS_PER_SECOND
+}
+
+impl Default for InsertConfig {
+    fn default() -> Self {
+        Self {
+            mode: InsertMode::default(),
+            chars_per_second: DEFAULT_CHARS_PER_SECOND,
+            prefer_code_block: false,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct InsertState {
+    last_answer: Mutex<Option<String>>,
+    inserting: AtomicBool,
+    cancel: AtomicBool,
+}
+
+// Remembers the latest final answer for the insert shortcut
+pub fn record_answer<R: Runtime>(app: &AppHandle<R>, answer: &str) {
+    if answer.trim().is_empty() {
+        return;
+    }
+    let state = app.state::<InsertState>();
+    let Ok(mut last_answer) = state.last_answer.lock() else {
+        return;
+    };
+    *last_answer = Some(answer.to_string());
+}
+
+// Contents of the fenced (```) code blocks in a markdown answer, in order
+pub fn extract_code_blocks(text: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            match current.take() {
+                Some(lines) => blocks.push(lines.join("\n")),
+                None => current = Some(Vec::new()),
+            }
+        } else if let Some(lines) = current.as_mut() {
+            lines.push(line);
+        }
+    }
+
+    blocks
+}
+
+// Modifiers of the insert shortcut may still be held (or seen as held) when the insert
+// starts, which would turn typed characters into shortcuts
+fn release_modifiers(enigo: &mut Enigo) -> Result<(), String> {
+    for key in [Key::Control, Key::Shift, Key::Alt, Key::Meta] {
+        enigo
+            .key(key, Direction::Release)
+            .map_err(|e| format!("Failed to release modifier keys: {}", e))?;
+    }
+    Ok(())
+}
+
+// On Linux this process serves the clipboard contents itself, and they are lost when the
+// handle drops; a thread keeps serving the restored text until something else is copied
+#[cfg(target_os = "linux")]
+fn restore_clipboard(clipboard: Clipboard, previous: String) {
+    use arboard::SetExtLinux;
+
+    std::thread::spawn(move || {
+        let mut clipboard = clipboard;
+        let _ = clipboard.set().wait().text(previous);
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+fn restore_clipboard(mut clipboard: Clipboard, previous: String) {
+    let _ = clipboard.set_text(previous);
+}
+
+fn paste_text(enigo: &mut Enigo, text: &str) -> Result<(), String> {
+    let mut clipboard = Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?;
+    let previous = clipboard.get_text().ok();
+    clipboard
+        .set_text(text)
+        .map_err(|e| format!("Failed to write clipboard: {}", e))?;
+
+    #[cfg(target_os = "macos")]
+    let modifier = Key::Meta;
+    #[cfg(not(target_os = "macos"))]
+    let modifier = Key::Control;
+
+    let result = enigo
+        .key(modifier, Direction::Press)
+        .and_then(|_| enigo.key(Key::Unicode('v'), Direction::Click))
+        .and_then(|_| enigo.key(modifier, Direction::Release))
+        .map_err(|e| format!("Failed to send paste shortcut: {}", e));
+
+    // Put back whatever the user had copied
+    std::thread::sleep(Duration::from_millis(CLIPBOARD_RESTORE_MS));
+    if let Some(previous) = previous {
+        restore_clipboard(clipboard, previous);
+    }
+    result
+}
+
+fn type_text(
+    enigo: &mut Enigo,
+    text: &str,
+    chars_per_second: u32,
+    cancel: &AtomicBool,
+) -> Result<(), String> {
+    let delay = Duration::from_secs_f64(1.0 / chars_per_second as f64);
+
+    for c in text.chars() {
+        if cancel.load(Ordering::Relaxed) {
+            return Err("Typing cancelled".to_string());
+        }
+        let result = match c {
+            '\n' => enigo.key(Key::Return, Direction::Click),
+            '\t' => enigo.key(Key::Tab, Direction::Click),
+            '\r' => Ok(()),
+            c => enigo.text(c.encode_utf8(&mut [0; 4])),
+        };
+        result.map_err(|e| format!("Failed to type text: {}", e))?;
+        std::thread::sleep(delay);
+    }
+
+    Ok(())
+}
+
+// The overlay may hold focus when the insert is started from its UI; hide it so the
+// keystrokes reach the application the user was working in
+async fn release_focus<R: Runtime>(app: &AppHandle<R>) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if window.is_focused().unwrap_or(false) {
+        if let Err(e) = window.hide() {
+            eprintln!("Failed to hide window: {}", e);
+        }
+        tokio::time::sleep(Duration::from_millis(FOCUS_SETTLE_MS)).await;
+    }
+}
+
+pub async fn insert_text<R: Runtime>(
+    app: &AppHandle<R>,
+    text: String,
+    mode: InsertMode,
+    chars_per_second: u32,
+) -> Result<(), String> {
+    if text.is_empty() {
+        return Err("Nothing to insert".to_string());
+    }
+    let state = app.state::<InsertState>();
+    if state.inserting.swap(true, Ordering::SeqCst) {
+        return Err("An answer is already being inserted".to_string());
+    }
+    state.cancel.store(false, Ordering::SeqCst);
+
+    release_focus(app).await;
+
+    let chars_per_second = chars_per_second.clamp(1, MAX_CHARS_PER_SECOND);
+    let app_handle = app.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let state = app_handle.state::<InsertState>();
+        let mut enigo = Enigo::new(&Settings::default())
+            .map_err(|e| format!("Failed to initialize keyboard input: {}", e))?;
+        release_modifiers(&mut enigo)?;
+        match mode {
+            InsertMode::Paste => paste_text(&mut enigo, &text),
+            InsertMode::Type => type_text(&mut enigo, &text, chars_per_second, &state.cancel),
+        }
+    })
+    .await
+    .map_err(|e| format!("Insert task failed: {}", e))
+    .and_then(|result| result);
+
+    state.inserting.store(false, Ordering::SeqCst);
+    result
+}
+
+// Shortcut entry point: inserts the last answer, or stops typing if an insert is running
+pub fn handle_insert_shortcut<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<InsertState>();
+    if state.inserting.load(Ordering::SeqCst) {
+        state.cancel.store(true, Ordering::SeqCst);
+        return;
+    }
+
+    let Some(answer) = state.last_answer.lock().ok().and_then(|a| a.clone()) else {
+        eprintln!("No answer to insert yet");
+        return;
+    };
+    let config = load_insert_config(app);
+    let text = if config.prefer_code_block {
+        extract_code_blocks(&answer).pop().unwrap_or(answer)
+    } else {
+        answer
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = insert_text(&app, text, config.mode, config.chars_per_second).await {
+            eprintln!("Failed to insert answer: {}", e);
+        }
+    });
+}
+
+fn get_insert_config_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+    Ok(app_data_dir.join("answer_insert.json"))
+}
+
+pub fn load_insert_config<R: Runtime>(app: &AppHandle<R>) -> InsertConfig {
+    let Ok(path) = get_insert_config_path(app) else {
+        return InsertConfig::default();
+    };
+
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// Inserts `text`, or the last answer when omitted. `code_block` picks one fenced code
+// block (0-based) from it; `mode` overrides the saved setting.
+#[tauri::command]
+pub async fn insert_answer(
+    app: AppHandle,
+    text: Option<String>,
+    code_block: Option<usize>,
+    mode: Option<InsertMode>,
+) -> Result<(), String> {
+    let text = match text {
+        Some(text) => text,
+        None => app
+            .state::<InsertState>()
+            .last_answer
+            .lock()
+            .map_err(|e| e.to_string())?
+            .clone()
+            .ok_or_else(|| "No answer to insert yet".to_string())?,
+    };
+    let text = match code_block {
+        Some(index) => extract_code_blocks(&text)
+            .into_iter()
+            .nth(index)
+            .ok_or_else(|| format!("Answer has no code block {}", index))?,
+        None => text,
+    };
+
+    let config = load_insert_config(&app);
+    insert_text(
+        &app,
+        text,
+        mode.unwrap_or(config.mode),
+        config.chars_per_second,
+    )
+    .await
+}
+
+#[tauri::command]
+pub fn cancel_answer_insert(app: AppHandle) {
+    app.state::<InsertState>()
+        .cancel
+        .store(true, Ordering::SeqCst);
+}
+
+#[tauri::command]
+pub fn get_insert_config(app: AppHandle) -> Result<InsertConfig, String> {
+    Ok(load_insert_config(&app))
+}
+
+#[tauri::command]
+pub fn save_insert_config(app: AppHandle, config: InsertConfig) -> Result<(), String> {
+    if config.chars_per_second == 0 || config.chars_per_second > MAX_CHARS_PER_SECOND {
+        return Err(format!(
+            "Typing rate must be between 1 and {} characters per second",
+            MAX_CHARS_PER_SECOND
+        ));
+    }
+
+    let path = get_insert_config_path(&app)?;
+    let content = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize insert config: {}", e))?;
+
+    fs::write(&path, content).map_err(|e| format!("Failed to write insert config: {}", e))
+}
@@ -1,3 +1,18 @@
+use crate::answer_insert::record_answer;
+use crate::app_context::{collect_app_context, load_app_context_config, ActiveAppContext};
+use crate::auth::{load_auth_overrides, resolve_endpoint_auth, EndpointAuth};
+use crate::chunking::{split_wav_for_upload, AudioChunk, TranscriptSegment};
+use crate::coalesce::{load_coalesce_config, ChunkCoalescer};
+use crate::error_rules::{
+    match_local_error_rule, record_error_diagnostic, ProviderError, ResolvedError,
+};
+use crate::inspector::{CallKind, InspectorCall};
+use crate::language::{load_language_setting, normalize_language, LanguageSetting};
+use crate::outbox::{queue_if_offline, OutboxPayload};
+use crate::question::handle_transcript;
+use crate::structured::{PartialJson, StructuredOutputRequest};
+use crate::transcription_cache::{self, CacheKey};
+use crate::vocabulary::{build_vocabulary_prompt, correct_transcript, load_vocabulary};
 use base64::{engine::general_purpose, Engine as _};
 use futures_util::StreamExt;
 use reqwest::multipart::{Form, Part};
@@ -6,10 +21,12 @@ use serde::{Deserialize, Serialize};
 use std::env;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_machine_uid::MachineUidExt;
 
-fn get_app_endpoint() -> Result<String, String> {
+pub(crate) fn get_app_endpoint() -> Result<String, String> {
     if let Ok(endpoint) = env::var("APP_ENDPOINT") {
         return Ok(endpoint);
     }
@@ -80,22 +97,84 @@ pub async fn get_stored_credentials(
     Ok((license_key, instance_id, selected_model))
 }
 
+const MAX_CONCURRENT_CHUNKS: usize = 3;
+
 // Audio API Structs
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AudioResponse {
-    success: bool,
-    transcription: Option<String>,
-    error: Option<String>,
+    pub success: bool,
+    pub transcription: Option<String>,
+    pub error: Option<String>,
+    // ISO 639-1 code of the transcribed language, when known
+    #[serde(default)]
+    pub language: Option<String>,
+    // Timed segments, when the provider reports them or the audio was chunked
+    #[serde(default)]
+    pub segments: Vec<TranscriptSegment>,
+}
+
+// Text returned by one transcription request
+pub(crate) struct Transcript {
+    pub text: String,
+    pub language: Option<String>,
+    // Mean segment log probability from `verbose_json`, used to compare attempts
+    pub avg_logprob: Option<f64>,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+// Progress of a chunked transcription, emitted as `transcription-progress`
+#[derive(Debug, Clone, Serialize)]
+struct TranscriptionProgress {
+    completed: usize,
+    total: usize,
+    duration_ms: u64,
+}
+
+impl Transcript {
+    // Moves segment times from chunk-relative to recording-relative. A chunk without
+    // provider segments becomes one segment spanning the chunk.
+    fn shifted(mut self, chunk: &AudioChunk) -> Self {
+        if self.segments.is_empty() {
+            self.segments.push(TranscriptSegment {
+                start_ms: 0,
+                end_ms: chunk.duration_ms,
+                text: self.text.trim().to_string(),
+            });
+        }
+        for segment in &mut self.segments {
+            segment.start_ms += chunk.offset_ms;
+            segment.end_ms += chunk.offset_ms;
+        }
+        self
+    }
+
+    // A forced language is known even when the provider does not echo it back
+    fn with_language(mut self, forced: Option<&str>) -> Self {
+        if let Some(language) = forced {
+            self.language = Some(language.to_string());
+        }
+        self
+    }
+}
+
+struct TranscriptionOptions<'a> {
+    language: Option<&'a str>,
+    verbose: bool,
+    vocabulary_prompt: Option<&'a str>,
 }
 
 // Chat API Structs
-#[allow(dead_code)]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatRequest {
-    user_message: String,
-    system_prompt: Option<String>,
-    image_base64: Option<serde_json::Value>, // Can be string or array
-    history: Option<String>,
+    pub user_message: String,
+    pub system_prompt: Option<String>,
+    pub image_base64: Option<serde_json::Value>, // Can be string or array
+    pub history: Option<String>,
+    #[serde(default)]
+    pub structured_output: Option<StructuredOutputRequest>,
+    // Captured when the request is made, so replays keep the original context
+    #[serde(default)]
+    pub app_context: Option<ActiveAppContext>,
 }
 
 #[allow(dead_code)]
@@ -145,6 +224,9 @@ pub struct ApiResponseConfig {
     #[serde(rename = "user_audio")]
     user_audio: Option<UserAudioConfig>,
     errors: Option<Vec<ApiConfigError>>,
+    auth: Option<EndpointAuth>,
+    // Provider model listing; derived from `url` when the server doesn't send one
+    models_url: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -172,6 +254,49 @@ pub struct UserAudioConfig {
     #[serde(rename = "fallback_user_token")]
     fallback_user_token: Option<String>,
     headers: Option<Vec<UserAudioHeader>>,
+    auth: Option<EndpointAuth>,
+    // Per-language endpoint/model overrides
+    #[serde(default)]
+    language_routes: Vec<LanguageRoute>,
+    // Form field that carries the vocabulary hint; providers with keyword boosting use
+    // their own field instead of Whisper's `prompt`
+    vocabulary_field: Option<String>,
+}
+
+impl UserAudioConfig {
+    // Every endpoint and model that may produce a transcript with this config, for the
+    // transcription cache key
+    fn cache_model(&self) -> String {
+        let mut parts = vec![format!("{}@{}", self.model, self.url)];
+        if let Some(fallback_url) = &self.fallback_url {
+            let fallback_model = self.fallback_model.as_deref().unwrap_or(&self.model);
+            parts.push(format!("{}@{}", fallback_model, fallback_url));
+        }
+        for route in &self.language_routes {
+            parts.push(format!(
+                "{}={}@{}",
+                route.language,
+                route.model.as_deref().unwrap_or(&self.model),
+                route.url.as_deref().unwrap_or(&self.url)
+            ));
+        }
+        parts.join(" ")
+    }
+
+    fn route_for(&self, language: &str) -> Option<&LanguageRoute> {
+        self.language_routes
+            .iter()
+            .find(|route| normalize_language(&route.language).as_deref() == Some(language))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LanguageRoute {
+    language: String,
+    url: Option<String>,
+    model: Option<String>,
+    #[serde(rename = "user_token")]
+    user_token: Option<String>,
 }
 
 // Audio API Command
@@ -179,6 +304,43 @@ pub struct UserAudioConfig {
 pub async fn transcribe_audio(
     app: AppHandle,
     audio_base64: String,
+    conversation_id: Option<String>,
+    source: Option<String>,
+) -> Result<AudioResponse, String> {
+    let audio_bytes = decode_audio_base64(&audio_base64)?;
+    match run_transcription(app.clone(), audio_bytes).await {
+        Ok(response) => {
+            // System-audio utterances may be questions to answer automatically
+            if let Some(text) = response
+                .transcription
+                .clone()
+                .filter(|text| !text.trim().is_empty())
+                .filter(|_| source.as_deref() == Some("system_audio"))
+            {
+                tauri::async_runtime::spawn({
+                    let app = app.clone();
+                    async move {
+                        handle_transcript(&app, &text).await;
+                    }
+                });
+            }
+            Ok(response)
+        }
+        Err(error) => Err(queue_if_offline(
+            &app,
+            conversation_id,
+            OutboxPayload::Transcription { audio_base64 },
+            error,
+        )
+        .await),
+    }
+}
+
+// Transcribes the audio with the configured endpoint, falling back to the secondary one,
+// and applies the user's language setting
+pub(crate) async fn run_transcription(
+    app: AppHandle,
+    audio_bytes: Vec<u8>,
 ) -> Result<AudioResponse, String> {
     let (_, _, selected_model) = get_stored_credentials(&app).await?;
     let provider = selected_model.as_ref().map(|model| model.provider.clone());
@@ -190,78 +352,340 @@ pub async fn transcribe_audio(
             .to_string()
     })?;
 
-    let audio_bytes = decode_audio_base64(&audio_base64)?;
-    let client = reqwest::Client::new();
+    // Checked before any transcription request so a hit returns immediately
+    let vocabulary = load_vocabulary(&app).await;
+    let setting = load_language_setting(&app);
+    let cache_key = CacheKey::new(
+        &audio_bytes,
+        &user_audio_config.cache_model(),
+        &serde_json::to_string(&(&setting, &vocabulary)).unwrap_or_default(),
+    );
+    if let Some(cached) = transcription_cache::lookup(&app, &cache_key).await {
+        return Ok(cached);
+    }
+
+    let auth = resolve_endpoint_auth(
+        load_auth_overrides(&app).transcription.as_ref(),
+        user_audio_config.auth.as_ref(),
+    );
+    let transcriber = Transcriber {
+        app: &app,
+        client: reqwest::Client::new(),
+        config: user_audio_config,
+        auth,
+        audio_bytes: &audio_bytes,
+        vocabulary_prompt: build_vocabulary_prompt(&vocabulary),
+    };
     let error_provider = provider.clone();
     let error_model = model.clone();
-    match perform_user_audio_transcription(
-        &client,
-        &user_audio_config.url,
-        &user_audio_config.user_token,
-        &user_audio_config.model,
-        user_audio_config.headers.as_ref(),
-        &audio_bytes,
-    )
-    .await
-    {
-        Ok(transcription) => Ok(AudioResponse {
-            success: true,
-            transcription: Some(transcription),
-            error: None,
-        }),
-        Err(primary_error) => {
-            let fallback_error_message = if let (Some(fallback_url), Some(fallback_token)) = (
-                user_audio_config.fallback_url.as_ref(),
-                user_audio_config.fallback_user_token.as_ref(),
-            ) {
-                let fallback_model = user_audio_config
-                    .fallback_model
-                    .as_ref()
-                    .unwrap_or(&user_audio_config.model);
-
-                match perform_user_audio_transcription(
-                    &client,
-                    fallback_url,
-                    fallback_token,
-                    fallback_model,
-                    user_audio_config.headers.as_ref(),
-                    &audio_bytes,
-                )
-                .await
-                {
-                    Ok(transcription) => {
-                        return Ok(AudioResponse {
-                            success: true,
-                            transcription: Some(transcription),
-                            error: None,
-                        });
-                    }
-                    Err(fallback_error) => Some(fallback_error),
-                }
-            } else {
-                Some("fallback not configured".to_string())
-            };
 
-            tracing::warn!(
-                primary_error = %primary_error,
-                fallback_error = %fallback_error_message
-                    .as_deref()
-                    .unwrap_or("not attempted"),
-                "Audio transcription failed for all configured endpoints"
+    let result = match split_wav_for_upload(&audio_bytes)? {
+        Some(chunks) => transcriber.transcribe_chunks(chunks, &setting).await,
+        None => transcriber.transcribe_with_setting(&setting).await,
+    };
+
+    match result {
+        Ok(transcript) => {
+            let response = AudioResponse {
+                success: true,
+                transcription: Some(correct_transcript(&transcript.text, &vocabulary)),
+                error: None,
+                language: transcript.language,
+                segments: transcript
+                    .segments
+                    .into_iter()
+                    .map(|segment| TranscriptSegment {
+                        text: correct_transcript(&segment.text, &vocabulary),
+                        ..segment
+                    })
+                    .collect(),
+            };
+            transcription_cache::store(&app, &cache_key, &response).await;
+            Ok(response)
+        }
+        Err(error) => {
+            let error_msg = error.to_string();
+            let final_message = resolve_provider_error(
+                &app,
+                error,
+                "Transcription failed. Please try again.".to_string(),
+                false,
+                error_provider.clone(),
+                error_model.clone(),
             );
             tauri::async_runtime::spawn({
                 let app = app.clone();
-                let error_msg = if let Some(fallback_err) = fallback_error_message {
-                    format!("Primary: {} | Fallback: {}", primary_error, fallback_err)
-                } else {
-                    primary_error.clone()
-                };
                 async move {
                     report_api_error(app, error_msg, "/api/transcribe".to_string(), error_model, error_provider).await;
                 }
             });
-            Err("Transcription failed. Please try again.".to_string())
+            Err(final_message)
+        }
+    }
+}
+
+// Endpoint label for transcription errors, like `/api/chat` for chat ones
+const TRANSCRIBE_ENDPOINT: &str = "/api/transcribe";
+
+// Everything needed to (re-)transcribe one audio clip with different language options
+struct Transcriber<'a> {
+    app: &'a AppHandle,
+    client: reqwest::Client,
+    config: &'a UserAudioConfig,
+    auth: EndpointAuth,
+    audio_bytes: &'a [u8],
+    vocabulary_prompt: Option<String>,
+}
+
+impl Transcriber<'_> {
+    // Same endpoint settings for another piece of audio
+    fn for_clip<'b>(&'b self, audio_bytes: &'b [u8]) -> Transcriber<'b> {
+        Transcriber {
+            app: self.app,
+            client: self.client.clone(),
+            config: self.config,
+            auth: self.auth.clone(),
+            audio_bytes,
+            vocabulary_prompt: self.vocabulary_prompt.clone(),
+        }
+    }
+
+    async fn transcribe_with_setting(
+        &self,
+        setting: &LanguageSetting,
+    ) -> Result<Transcript, ProviderError> {
+        match setting {
+            LanguageSetting::Auto => self.transcribe_detected().await,
+            LanguageSetting::Fixed { language } => {
+                self.transcribe(Some(language.as_str()), false).await
+            }
+            LanguageSetting::Allowed { languages } => self.transcribe_allowed(languages).await,
+        }
+    }
+
+    // Transcribes chunks of a long recording a few at a time and stitches the text back
+    // together, shifting segment times by each chunk's offset
+    async fn transcribe_chunks(
+        &self,
+        chunks: Vec<AudioChunk>,
+        setting: &LanguageSetting,
+    ) -> Result<Transcript, ProviderError> {
+        let total = chunks.len();
+        let duration_ms = chunks
+            .last()
+            .map(|chunk| chunk.offset_ms + chunk.duration_ms)
+            .unwrap_or_default();
+        let completed = &AtomicUsize::new(0);
+        let emit_progress = &|completed: usize| {
+            let progress = TranscriptionProgress {
+                completed,
+                total,
+                duration_ms,
+            };
+            let _ = self.app.emit("transcription-progress", &progress);
+        };
+        emit_progress(0);
+
+        let results: Vec<Result<Transcript, ProviderError>> = futures_util::stream::iter(chunks)
+            .map(|chunk| async move {
+                let result = self
+                    .for_clip(&chunk.bytes)
+                    .transcribe_with_setting(setting)
+                    .await
+                    .map(|transcript| transcript.shifted(&chunk));
+                emit_progress(completed.fetch_add(1, Ordering::SeqCst) + 1);
+                result
+            })
+            .buffered(MAX_CONCURRENT_CHUNKS)
+            .collect()
+            .await;
+
+        let mut stitched = Transcript {
+            text: String::new(),
+            language: None,
+            avg_logprob: None,
+            segments: Vec::new(),
+        };
+        for (index, result) in results.into_iter().enumerate() {
+            let transcript = result.inspect_err(|e| {
+                tracing::warn!("Chunk {} of {} failed: {}", index + 1, total, e);
+            })?;
+            let text = transcript.text.trim();
+            if !text.is_empty() {
+                if !stitched.text.is_empty() {
+                    stitched.text.push(' ');
+                }
+                stitched.text.push_str(text);
+            }
+            stitched.language = stitched.language.or(transcript.language);
+            stitched.segments.extend(transcript.segments);
+        }
+
+        Ok(stitched)
+    }
+
+    // One transcription with the endpoint routed for `language`. `verbose` asks for
+    // `verbose_json` so the language is reported; models that reject it (e.g.
+    // gpt-4o-transcribe) answer 400, and are asked again without it.
+    async fn transcribe(
+        &self,
+        language: Option<&str>,
+        verbose: bool,
+    ) -> Result<Transcript, ProviderError> {
+        match self.transcribe_once(language, verbose).await {
+            Err(error) if verbose && error.status == Some(400) => {
+                tracing::info!("Retrying transcription without verbose_json: {}", error);
+                self.transcribe_once(language, false).await
+            }
+            result => result,
+        }
+    }
+
+    // One attempt at the routed endpoint, falling back to the secondary one
+    async fn transcribe_once(
+        &self,
+        language: Option<&str>,
+        verbose: bool,
+    ) -> Result<Transcript, ProviderError> {
+        let config = self.config;
+        let route = language.and_then(|language| config.route_for(language));
+        let url = route.and_then(|r| r.url.as_ref()).unwrap_or(&config.url);
+        let token = route
+            .and_then(|r| r.user_token.as_ref())
+            .unwrap_or(&config.user_token);
+        let model = route
+            .and_then(|r| r.model.as_ref())
+            .unwrap_or(&config.model);
+        let options = TranscriptionOptions {
+            language,
+            verbose,
+            vocabulary_prompt: self.vocabulary_prompt.as_deref(),
+        };
+
+        let primary_error = match perform_user_audio_transcription(
+            self.app,
+            &self.client,
+            url,
+            token,
+            model,
+            config.headers.as_ref(),
+            &self.auth,
+            self.audio_bytes,
+            &options,
+            config.vocabulary_field.as_deref(),
+        )
+        .await
+        {
+            Ok(transcript) => return Ok(transcript.with_language(language)),
+            Err(primary_error) => primary_error,
+        };
+
+        let fallback_error = if let (Some(fallback_url), Some(fallback_token)) = (
+            config.fallback_url.as_ref(),
+            config.fallback_user_token.as_ref(),
+        ) {
+            let fallback_model = config.fallback_model.as_ref().unwrap_or(&config.model);
+
+            match perform_user_audio_transcription(
+                self.app,
+                &self.client,
+                fallback_url,
+                fallback_token,
+                fallback_model,
+                config.headers.as_ref(),
+                &self.auth,
+                self.audio_bytes,
+                &options,
+                config.vocabulary_field.as_deref(),
+            )
+            .await
+            {
+                Ok(transcript) => return Ok(transcript.with_language(language)),
+                Err(fallback_error) => Some(fallback_error),
+            }
+        } else {
+            None
+        };
+
+        // The primary error is the one reported; the fallback only shows up in the log
+        tracing::warn!(
+            primary_error = %primary_error,
+            fallback_error = %fallback_error
+                .map_or("fallback not configured".to_string(), |e| e.to_string()),
+            "Audio transcription failed for all configured endpoints"
+        );
+        Err(primary_error)
+    }
+
+    // Lets the provider detect the language. It is only reported in the verbose response
+    // format, which is requested only when a language route needs it. If the detected
+    // language has its own route, the clip is transcribed again with that endpoint/model.
+    async fn transcribe_detected(&self) -> Result<Transcript, ProviderError> {
+        let routed = !self.config.language_routes.is_empty();
+        let detected = self.transcribe(None, routed).await?;
+
+        let Some(language) = detected.language.clone() else {
+            return Ok(detected);
+        };
+        if self.config.route_for(&language).is_none() {
+            return Ok(detected);
+        }
+
+        match self.transcribe(Some(language.as_str()), false).await {
+            Ok(routed) => Ok(routed),
+            Err(error) => {
+                tracing::warn!("Routed transcription for '{}' failed: {}", language, error);
+                Ok(detected)
+            }
+        }
+    }
+
+    // Detects the language; when it is not in the allowed list, transcribes again forcing
+    // each allowed language and keeps the most confident result
+    async fn transcribe_allowed(&self, languages: &[String]) -> Result<Transcript, ProviderError> {
+        match languages {
+            [] => return self.transcribe_detected().await,
+            [language] => return self.transcribe(Some(language.as_str()), false).await,
+            _ => {}
+        }
+
+        let detected = self.transcribe(None, true).await?;
+        if let Some(language) = detected.language.as_ref().filter(|l| languages.contains(l)) {
+            if self.config.route_for(language).is_none() {
+                return Ok(detected);
+            }
+            let language = language.clone();
+            return Ok(self
+                .transcribe(Some(language.as_str()), false)
+                .await
+                .unwrap_or(detected));
         }
+
+        tracing::info!(
+            "Detected language {:?} is not allowed, retrying with {:?}",
+            detected.language,
+            languages
+        );
+        let attempts = futures_util::future::join_all(
+            languages
+                .iter()
+                .map(|language| self.transcribe(Some(language.as_str()), true)),
+        )
+        .await;
+
+        let best = attempts
+            .into_iter()
+            .filter_map(Result::ok)
+            .reduce(|best, candidate| {
+                let score = |t: &Transcript| t.avg_logprob.unwrap_or(f64::NEG_INFINITY);
+                if score(&candidate) > score(&best) {
+                    candidate
+                } else {
+                    best
+                }
+            });
+
+        Ok(best.unwrap_or(detected))
     }
 }
 
@@ -299,7 +723,13 @@ async fn fetch_api_response_config(
         request = request.header("model", m);
     }
 
-    let response = request.send().await.map_err(|e| {
+    let request = request
+        .build()
+        .map_err(|e| format!("Failed to fetch API config: {}", e))?;
+    let mut inspector_call = InspectorCall::begin(app, CallKind::Config, Some(&request), None);
+
+    let response = client.execute(request).await.map_err(|e| {
+        inspector_call.set_error(e.to_string());
         let error_msg = format!("{}", e);
         if error_msg.contains("url (") {
             let parts: Vec<&str> = error_msg.split(" for url (").collect();
@@ -320,6 +750,8 @@ async fn fetch_api_response_config(
             .text()
             .await
             .unwrap_or_else(|_| "Unknown server error".to_string());
+        inspector_call.set_status(status.as_u16());
+        inspector_call.set_response_body(&error_text);
 
         // Try to parse error as JSON to get a more specific error message
         if let Ok(error_json) = serde_json::from_str::<serde_json::Value>(&error_text) {
@@ -332,10 +764,14 @@ async fn fetch_api_response_config(
 
         return Err(format!("Server error ({}): {}", status, error_text));
     }
-    let api_config: ApiResponseConfig = response
-        .json()
+    inspector_call.set_status(response.status().as_u16());
+    let body_text = response
+        .text()
         .await
         .map_err(|e| format!("Failed to parse API config response: {}", e))?;
+    inspector_call.set_response_body(&body_text);
+    let api_config: ApiResponseConfig = serde_json::from_str(&body_text)
+        .map_err(|e| format!("Failed to parse API config response: {}", e))?;
     Ok(api_config)
 }
 
@@ -364,7 +800,25 @@ fn map_api_error_message(error_rules: &[ApiConfigError], sources: &[String]) ->
         })
 }
 
-fn decode_audio_base64(audio_base64: &str) -> Result<Vec<u8>, String> {
+// Picks the user-facing message for a failed provider call (local rules first, then the
+// server-provided message) and keeps the raw error for the diagnostics view
+fn resolve_provider_error(
+    app: &AppHandle,
+    error: ProviderError,
+    server_message: String,
+    has_server_rules: bool,
+    provider: Option<String>,
+    model: Option<String>,
+) -> String {
+    let resolved = match_local_error_rule(app, &error)
+        .unwrap_or_else(|| ResolvedError::from_server(server_message, &error, has_server_rules));
+
+    let diagnostic = record_error_diagnostic(app, error, resolved, provider, model);
+    let _ = app.emit("provider-error", &diagnostic);
+    diagnostic.resolved.message
+}
+
+pub(crate) fn decode_audio_base64(audio_base64: &str) -> Result<Vec<u8>, String> {
     let trimmed = audio_base64.trim();
     let base64_str = if let Some(idx) = trimmed.find(',') {
         &trimmed[idx + 1..]
@@ -377,22 +831,32 @@ fn decode_audio_base64(audio_base64: &str) -> Result<Vec<u8>, String> {
         .map_err(|e| format!("Failed to decode audio data: {}", e))
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn perform_user_audio_transcription(
+    app: &AppHandle,
     client: &reqwest::Client,
     url: &str,
     token: &str,
     model: &str,
     headers: Option<&Vec<UserAudioHeader>>,
+    auth: &EndpointAuth,
     audio_bytes: &[u8],
-) -> Result<String, String> {
+    options: &TranscriptionOptions<'_>,
+    vocabulary_field: Option<&str>,
+) -> Result<Transcript, ProviderError> {
     let audio_part = Part::bytes(audio_bytes.to_vec())
         .file_name("audio.wav")
         .mime_str("audio/wav")
-        .map_err(|e| format!("Failed to prepare audio payload: {}", e))?;
+        .map_err(|e| ProviderError::from_transport(TRANSCRIBE_ENDPOINT, &e))?;
 
     let mut form = Form::new()
         .part("file", audio_part)
         .text("model", model.to_string());
+    // Multipart bodies can't be inspected after building, so log a summary of the fields
+    let mut body_summary = serde_json::json!({
+        "file": format!("audio.wav ({} bytes)", audio_bytes.len()),
+        "model": model,
+    });
 
     if let Some(extra_headers) = headers {
         for header in extra_headers {
@@ -401,17 +865,47 @@ async fn perform_user_audio_transcription(
                 continue;
             }
 
+            body_summary[key] = serde_json::Value::String(header.value.clone());
             form = form.text(key.to_string(), header.value.clone());
         }
     }
 
-    let response = client
-        .post(url)
-        .bearer_auth(token)
+    if let Some(language) = options.language {
+        body_summary["language"] = serde_json::Value::String(language.to_string());
+        form = form.text("language", language.to_string());
+    }
+    let has_field = |name: &str| {
+        headers.is_some_and(|headers| headers.iter().any(|header| header.key.trim() == name))
+    };
+    if options.verbose && !has_field("response_format") {
+        body_summary["response_format"] = serde_json::Value::String("verbose_json".to_string());
+        form = form.text("response_format", "verbose_json");
+    }
+    if let Some(prompt) = options.vocabulary_prompt {
+        let field = vocabulary_field.unwrap_or("prompt");
+        if !has_field(field) {
+            body_summary[field] = serde_json::Value::String(prompt.to_string());
+            form = form.text(field.to_string(), prompt.to_string());
+        }
+    }
+
+    let request = auth
+        .apply(client.post(url), token)
         .multipart(form)
-        .send()
-        .await
-        .map_err(|e| format!("Transcription request failed to send: {}", e))?;
+        .build()
+        .map_err(|e| ProviderError::from_transport(TRANSCRIBE_ENDPOINT, &e))?;
+    let mut inspector_call = InspectorCall::begin(
+        app,
+        CallKind::Transcription,
+        Some(&request),
+        Some(&body_summary),
+    );
+
+    let response = client.execute(request).await.map_err(|e| {
+        inspector_call.set_error(e.to_string());
+        ProviderError::from_transport(TRANSCRIBE_ENDPOINT, &e)
+    })?;
+    inspector_call.set_status(response.status().as_u16());
 
     if !response.status().is_success() {
         let status = response.status();
@@ -419,40 +913,173 @@ async fn perform_user_audio_transcription(
             .text()
             .await
             .unwrap_or_else(|_| "Unable to read transcription error response".to_string());
-        return Err(format!(
-            "Transcription request returned {} with body: {}",
-            status, error_text
+        inspector_call.set_response_body(&error_text);
+        return Err(ProviderError::from_response(
+            TRANSCRIBE_ENDPOINT,
+            status.as_u16(),
+            &error_text,
         ));
     }
 
     let body_text = response
         .text()
         .await
-        .map_err(|e| format!("Failed to read transcription response: {}", e))?;
+        .map_err(|e| ProviderError::from_transport(TRANSCRIBE_ENDPOINT, &e))?;
+    inspector_call.set_response_body(&body_text);
 
     if body_text.trim().is_empty() {
-        return Err("Transcription response was empty".to_string());
+        return Err(ProviderError::local(
+            TRANSCRIBE_ENDPOINT,
+            "empty_response",
+            "Transcription response was empty",
+        ));
     }
 
     if let Ok(json) = serde_json::from_str::<serde_json::Value>(&body_text) {
-        if let Some(text) = json.get("text").and_then(|value| value.as_str()) {
-            return Ok(text.to_string());
-        }
-
-        if let Some(text) = json
-            .get("transcription")
+        let text = json
+            .get("text")
             .and_then(|value| value.as_str())
+            .or_else(|| json.get("transcription").and_then(|value| value.as_str()))
             .or_else(|| json.get("result").and_then(|value| value.as_str()))
-        {
-            return Ok(text.to_string());
+            .map(|text| text.to_string())
+            .unwrap_or_else(|| json.to_string());
+
+        return Ok(Transcript {
+            text,
+            language: json
+                .get("language")
+                .and_then(|value| value.as_str())
+                .and_then(normalize_language),
+            avg_logprob: mean_segment_logprob(&json),
+            segments: parse_segments(&json),
+        });
+    }
+
+    Ok(Transcript {
+        text: body_text,
+        language: None,
+        avg_logprob: None,
+        segments: Vec::new(),
+    })
+}
+
+// `verbose_json` segments, with times in seconds
+fn parse_segments(json: &serde_json::Value) -> Vec<TranscriptSegment> {
+    let Some(segments) = json.get("segments").and_then(|value| value.as_array()) else {
+        return Vec::new();
+    };
+
+    segments
+        .iter()
+        .filter_map(|segment| {
+            let start = segment.get("start")?.as_f64()?;
+            let end = segment.get("end")?.as_f64()?;
+            let text = segment.get("text")?.as_str()?;
+            Some(TranscriptSegment {
+                start_ms: (start * 1000.0) as u64,
+                end_ms: (end * 1000.0) as u64,
+                text: text.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+fn mean_segment_logprob(json: &serde_json::Value) -> Option<f64> {
+    let logprobs: Vec<f64> = json
+        .get("segments")?
+        .as_array()?
+        .iter()
+        .filter_map(|segment| segment.get("avg_logprob").and_then(|value| value.as_f64()))
+        .collect();
+
+    if logprobs.is_empty() {
+        None
+    } else {
+        Some(logprobs.iter().sum::<f64>() / logprobs.len() as f64)
+    }
+}
+
+// Where the events of a streamed chat completion go
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChatEvents {
+    // The overlay chat: `chat_stream_*` events, including structured output
+    Frontend,
+    // Background requests (outbox replay): no stream events at all
+    Silent,
+    // Backend-triggered answers, streamed under their own event names
+    Named {
+        chunk: &'static str,
+        complete: &'static str,
+    },
+}
+
+impl ChatEvents {
+    fn chunk_event(self) -> Option<&'static str> {
+        match self {
+            ChatEvents::Frontend => Some("chat_stream_chunk"),
+            ChatEvents::Silent => None,
+            ChatEvents::Named { chunk, .. } => Some(chunk),
         }
+    }
+
+    fn complete_event(self) -> Option<&'static str> {
+        match self {
+            ChatEvents::Frontend => Some("chat_stream_complete"),
+            ChatEvents::Silent => None,
+            ChatEvents::Named { complete, .. } => Some(complete),
+        }
+    }
+}
+
+// Emits one coalesced batch of streamed text, plus the latest structured snapshot if any
+fn emit_stream_batch(
+    app: &AppHandle,
+    events: ChatEvents,
+    batch: String,
+    partial_json: Option<&PartialJson>,
+) {
+    if let Some(event) = events.chunk_event() {
+        let _ = app.emit(event, batch);
+    }
+    if events == ChatEvents::Frontend {
+        if let Some(snapshot) = partial_json.and_then(|partial| partial.snapshot()) {
+            let _ = app.emit("chat_stream_structured_partial", snapshot);
+        }
+    }
+}
 
-        return Ok(json.to_string());
+// Extracts the streamed text from an OpenAI-style delta. Forced tool calls stream their
+// JSON through `tool_calls[].function.arguments` instead of `content`, or through
+// `input_json_delta` events in Anthropic's format.
+fn extract_delta_content(parsed: &serde_json::Value) -> Option<&str> {
+    if parsed.get("type").and_then(|t| t.as_str()) == Some("content_block_delta") {
+        let delta = parsed.get("delta")?;
+        return delta
+            .get("text")
+            .or_else(|| delta.get("partial_json"))
+            .and_then(|text| text.as_str());
     }
 
-    Ok(body_text)
+    let delta = parsed
+        .get("choices")
+        .and_then(|c| c.as_array())
+        .and_then(|choices| choices.first())
+        .and_then(|choice| choice.get("delta"))?;
+
+    if let Some(content) = delta.get("content").and_then(|c| c.as_str()) {
+        return Some(content);
+    }
+
+    delta
+        .get("tool_calls")
+        .and_then(|calls| calls.as_array())
+        .and_then(|calls| calls.first())
+        .and_then(|call| call.get("function"))
+        .and_then(|function| function.get("arguments"))
+        .and_then(|arguments| arguments.as_str())
 }
 
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 pub async fn chat_stream_response(
     app: AppHandle,
@@ -460,13 +1087,69 @@ pub async fn chat_stream_response(
     system_prompt: Option<String>,
     image_base64: Option<serde_json::Value>,
     history: Option<String>,
+    structured_output: Option<StructuredOutputRequest>,
+    conversation_id: Option<String>,
+    include_app_context: Option<bool>,
+) -> Result<String, String> {
+    let context_config = load_app_context_config(&app);
+    let app_context = if include_app_context.unwrap_or(context_config.enabled) {
+        collect_app_context(&app, context_config.include_selection)
+            .await
+            .map_err(|e| tracing::warn!("Skipping app context: {}", e))
+            .ok()
+    } else {
+        None
+    };
+
+    let request = ChatRequest {
+        user_message,
+        system_prompt,
+        image_base64,
+        history,
+        structured_output,
+        app_context,
+    };
+
+    match run_chat_stream(app.clone(), request.clone(), ChatEvents::Frontend).await {
+        Ok(response) => {
+            record_answer(&app, &response);
+            Ok(response)
+        }
+        Err(error) => {
+            let payload = OutboxPayload::Chat(Box::new(request));
+            Err(queue_if_offline(&app, conversation_id, payload, error).await)
+        }
+    }
+}
+
+// Runs one chat completion. `events` decides which stream events are emitted, so
+// background callers (like the outbox) don't interfere with the visible chat.
+pub(crate) async fn run_chat_stream(
+    app: AppHandle,
+    request: ChatRequest,
+    events: ChatEvents,
 ) -> Result<String, String> {
+    let emit_events = events != ChatEvents::Silent;
+    let ChatRequest {
+        user_message,
+        system_prompt,
+        image_base64,
+        history,
+        structured_output,
+        app_context,
+    } = request;
+
     // Get stored credentials to get selected model
     let (_, _, selected_model) = get_stored_credentials(&app).await?;
     let (provider, model) = selected_model.as_ref().map_or((None, None), |m| {
         (Some(m.provider.clone()), Some(m.model.clone()))
     });
 
+    // Resolve structured output before any network call so bad schemas fail fast
+    let structured_output = structured_output
+        .map(|request| request.resolve(provider.as_deref()))
+        .transpose()?;
+
     // Fetch API configuration
     let api_config = fetch_api_response_config(&app, provider.clone(), model.clone()).await?;
 
@@ -488,6 +1171,14 @@ pub async fn chat_stream_response(
         }));
     }
 
+    // Add what the user is looking at as a separate system note
+    if let Some(context) = app_context.as_ref() {
+        messages.push(serde_json::json!({
+            "role": "system",
+            "content": context.system_note()
+        }));
+    }
+
     // Add history if provided
     if let Some(history_str) = history {
         if let Ok(history_messages) = serde_json::from_str::<Vec<serde_json::Value>>(&history_str) {
@@ -557,24 +1248,49 @@ pub async fn chat_stream_response(
         }
     }
 
+    // Structured output constraints take precedence over config-provided formats
+    if let Some(structured) = structured_output.as_ref() {
+        structured.apply_to_body(&mut request_body);
+    }
+
     // Make HTTP request to the configured endpoint with streaming
     let client = reqwest::Client::new();
     let error_rules = api_config.errors.clone().unwrap_or_default();
-    let response = match client
-        .post(&api_config.url)
+    let auth = resolve_endpoint_auth(
+        load_auth_overrides(&app).chat.as_ref(),
+        api_config.auth.as_ref(),
+    );
+    let request = auth
+        .apply(client.post(&api_config.url), &api_config.user_token)
         .header("Content-Type", "application/json")
-        .header("Authorization", format!("Bearer {}", api_config.user_token))
         .json(&request_body)
-        .send()
-        .await
-    {
+        .build();
+    let mut inspector_call = InspectorCall::begin(
+        &app,
+        CallKind::Chat,
+        request.as_ref().ok(),
+        Some(&request_body),
+    );
+    let sent = match request {
+        Ok(request) => client.execute(request).await,
+        Err(e) => Err(e),
+    };
+    let response = match sent {
         Ok(resp) => resp,
         Err(e) => {
+            inspector_call.set_error(e.to_string());
             let mut sources = vec![e.to_string()];
             if let Ok(url) = Url::parse(&api_config.url) {
                 sources.push(url.to_string());
             }
-            let final_message = map_api_error_message(&error_rules, &sources);
+            let final_message = resolve_provider_error(
+                &app,
+                ProviderError::from_transport("/api/chat", &e),
+                map_api_error_message(&error_rules, &sources),
+                !error_rules.is_empty(),
+                provider.clone(),
+                model.clone(),
+            );
             tauri::async_runtime::spawn({
                 let app = app.clone();
                 let provider = provider.clone();
@@ -588,6 +1304,8 @@ pub async fn chat_stream_response(
         }
     };
 
+    inspector_call.set_status(response.status().as_u16());
+
     // Check if the response is successful
     if !response.status().is_success() {
         let status = response.status();
@@ -595,6 +1313,7 @@ pub async fn chat_stream_response(
             .text()
             .await
             .unwrap_or_else(|_| "Unknown server error".to_string());
+        inspector_call.set_response_body(&error_text);
 
         let mut sources = vec![error_text.clone(), status.to_string()];
 
@@ -608,7 +1327,14 @@ pub async fn chat_stream_response(
             }
         }
 
-        let final_message = map_api_error_message(&error_rules, &sources);
+        let final_message = resolve_provider_error(
+            &app,
+            ProviderError::from_response("/api/chat", status.as_u16(), &error_text),
+            map_api_error_message(&error_rules, &sources),
+            !error_rules.is_empty(),
+            provider.clone(),
+            model.clone(),
+        );
         tauri::async_runtime::spawn({
             let app = app.clone();
             let provider = provider.clone();
@@ -627,8 +1353,30 @@ pub async fn chat_stream_response(
     let mut buffer = String::new();
     let mut usage: Option<serde_json::Value> = None;
     let mut stream_started = false;
+    let mut partial_json = structured_output.as_ref().map(|_| PartialJson::new());
+    // Deltas are batched before they are emitted, see `coalesce`
+    let mut coalescer = ChunkCoalescer::new(load_coalesce_config(&app));
+
+    loop {
+        let next = match coalescer.deadline() {
+            Some(deadline) => {
+                match tokio::time::timeout_at(deadline.into(), stream.next()).await {
+                    Ok(next) => next,
+                    Err(_) => {
+                        // Nothing arrived before the flush interval ran out
+                        if let Some(batch) = coalescer.flush().filter(|_| emit_events) {
+                            emit_stream_batch(&app, events, batch, partial_json.as_ref());
+                        }
+                        continue;
+                    }
+                }
+            }
+            None => stream.next().await,
+        };
+        let Some(chunk) = next else {
+            break;
+        };
 
-    while let Some(chunk) = stream.next().await {
         match chunk {
             Ok(bytes) => {
                 let chunk_str = String::from_utf8_lossy(&bytes);
@@ -641,6 +1389,9 @@ pub async fn chat_stream_response(
                 for line in &lines[..lines.len() - 1] {
                     // Process all but the last (potentially incomplete) line
                     let trimmed_line = line.trim();
+                    if !trimmed_line.is_empty() {
+                        inspector_call.push_event(trimmed_line);
+                    }
 
                     if trimmed_line.starts_with("data: ") {
                         let json_str = trimmed_line.strip_prefix("data: ").unwrap_or("");
@@ -660,21 +1411,43 @@ pub async fn chat_stream_response(
                                         }
                                     }
                                 }
-                                if let Some(choices) =
-                                    parsed.get("choices").and_then(|c| c.as_array())
-                                {
-                                    if let Some(first_choice) = choices.first() {
-                                        if let Some(delta) = first_choice.get("delta") {
-                                            if let Some(content) =
-                                                delta.get("content").and_then(|c| c.as_str())
+                                if let Some(content) = extract_delta_content(&parsed) {
+                                    full_response.push_str(content);
+                                    inspector_call.mark_first_token();
+                                    stream_started = true;
+
+                                    // Validate structured output as it streams in
+                                    if let Some(partial) = partial_json.as_mut() {
+                                        if let Err(e) = partial.push(content) {
+                                            inspector_call.set_error(e.clone());
+                                            if let Some(batch) =
+                                                coalescer.flush().filter(|_| emit_events)
                                             {
-                                                full_response.push_str(content);
-                                                // Emit just the content to frontend
-                                                let _ = app.emit("chat_stream_chunk", content);
-                                                stream_started = true;
+                                                emit_stream_batch(&app, events, batch, None);
                                             }
+                                            if events == ChatEvents::Frontend {
+                                                let _ =
+                                                    app.emit("chat_stream_structured_error", &e);
+                                            }
+                                            return Err(format!(
+                                                "The model returned malformed structured output: {}",
+                                                e
+                                            ));
                                         }
                                     }
+
+                                    // Emit just the content to frontend, once a batch is due
+                                    if let Some(batch) = coalescer
+                                        .push(content, Instant::now())
+                                        .filter(|_| emit_events)
+                                    {
+                                        emit_stream_batch(
+                                            &app,
+                                            events,
+                                            batch,
+                                            partial_json.as_ref(),
+                                        );
+                                    }
                                 }
                             }
                         }
@@ -685,8 +1458,19 @@ pub async fn chat_stream_response(
                 buffer = incomplete_line;
             }
             Err(e) => {
+                inspector_call.set_error(e.to_string());
+                if let Some(batch) = coalescer.flush().filter(|_| emit_events) {
+                    emit_stream_batch(&app, events, batch, partial_json.as_ref());
+                }
                 let sources = vec![e.to_string()];
-                let final_message = map_api_error_message(&error_rules, &sources);
+                let final_message = resolve_provider_error(
+                    &app,
+                    ProviderError::from_transport("/api/chat", &e),
+                    map_api_error_message(&error_rules, &sources),
+                    !error_rules.is_empty(),
+                    provider.clone(),
+                    model.clone(),
+                );
                 tauri::async_runtime::spawn({
                     let app = app.clone();
                     let provider = provider.clone();
@@ -701,8 +1485,35 @@ pub async fn chat_stream_response(
         }
     }
 
-    // Emit completion event
-    let _ = app.emit("chat_stream_complete", &full_response);
+    // Flush the last batch
+    if emit_events {
+        if let Some(batch) = coalescer.flush() {
+            emit_stream_batch(&app, events, batch, partial_json.as_ref());
+        }
+    }
+
+    // Validate the final structured answer and hand the typed result to the frontend. This
+    // runs before the completion event so a rejected answer is never reported as complete.
+    if let Some(structured) = structured_output.as_ref() {
+        match structured.finish(&full_response) {
+            Ok(result) => {
+                if events == ChatEvents::Frontend {
+                    let _ = app.emit("chat_stream_structured_complete", &result);
+                }
+            }
+            Err(e) => {
+                inspector_call.set_error(e.clone());
+                if events == ChatEvents::Frontend {
+                    let _ = app.emit("chat_stream_structured_error", &e);
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    if let Some(event) = events.complete_event() {
+        let _ = app.emit(event, &full_response);
+    }
 
     if stream_started && !full_response.is_empty() {
         tauri::async_runtime::spawn({
@@ -862,35 +1673,39 @@ async fn report_api_error(
 
 // Models API Command
 #[tauri::command]
-pub async fn fetch_models() -> Result<Vec<Model>, String> {
+pub async fn fetch_models(app: AppHandle) -> Result<Vec<Model>, String> {
     // Get environment variables
     let app_endpoint = get_app_endpoint()?;
     let api_access_key = get_api_access_key()?;
 
-    // Make HTTP request to models endpoint
+    // Make HTTP request to models endpoint. This is Pluely's own API, so it always uses the
+    // app's key; auth overrides only apply to provider endpoints.
     let client = reqwest::Client::new();
     let url = format!("{}/api/models", app_endpoint);
 
-    let response = client
+    let request = client
         .post(&url)
         .header("Content-Type", "application/json")
         .header("Authorization", format!("Bearer {}", api_access_key))
-        .send()
-        .await
-        .map_err(|e| {
-            let error_msg = format!("{}", e);
-            if error_msg.contains("url (") {
-                // Remove the URL part from the error message
-                let parts: Vec<&str> = error_msg.split(" for url (").collect();
-                if parts.len() > 1 {
-                    format!("Failed to make models request: {}", parts[0])
-                } else {
-                    format!("Failed to make models request: {}", error_msg)
-                }
+        .build()
+        .map_err(|e| format!("Failed to make models request: {}", e))?;
+    let mut inspector_call = InspectorCall::begin(&app, CallKind::Models, Some(&request), None);
+
+    let response = client.execute(request).await.map_err(|e| {
+        inspector_call.set_error(e.to_string());
+        let error_msg = format!("{}", e);
+        if error_msg.contains("url (") {
+            // Remove the URL part from the error message
+            let parts: Vec<&str> = error_msg.split(" for url (").collect();
+            if parts.len() > 1 {
+                format!("Failed to make models request: {}", parts[0])
             } else {
                 format!("Failed to make models request: {}", error_msg)
             }
-        })?;
+        } else {
+            format!("Failed to make models request: {}", error_msg)
+        }
+    })?;
 
     // Check if the response is successful
     if !response.status().is_success() {
@@ -899,6 +1714,8 @@ pub async fn fetch_models() -> Result<Vec<Model>, String> {
             .text()
             .await
             .unwrap_or_else(|_| "Unknown server error".to_string());
+        inspector_call.set_status(status.as_u16());
+        inspector_call.set_response_body(&error_text);
 
         // Try to parse error as JSON to get a more specific error message
         if let Ok(error_json) = serde_json::from_str::<serde_json::Value>(&error_text) {
@@ -912,14 +1729,103 @@ pub async fn fetch_models() -> Result<Vec<Model>, String> {
         return Err(format!("Server error ({}): {}", status, error_text));
     }
 
-    let models_response: ModelsResponse = response
-        .json()
+    inspector_call.set_status(response.status().as_u16());
+    let body_text = response
+        .text()
         .await
         .map_err(|e| format!("Failed to parse models response: {}", e))?;
+    inspector_call.set_response_body(&body_text);
+    let models_response: ModelsResponse = serde_json::from_str(&body_text)
+        .map_err(|e| format!("Failed to parse models response: {}", e))?;
 
     Ok(models_response.models)
 }
 
+#[derive(Debug, Deserialize)]
+struct ProviderModel {
+    id: String,
+}
+
+// OpenAI-style list, also used by Azure and Anthropic
+#[derive(Debug, Deserialize)]
+struct ProviderModelsResponse {
+    data: Vec<ProviderModel>,
+}
+
+const CHAT_PATH_SUFFIXES: [&str; 4] = [
+    "/chat/completions",
+    "/completions",
+    "/messages",
+    "/responses",
+];
+
+// The provider's own `/models` endpoint next to the chat one, e.g. `.../v1/chat/completions`
+// becomes `.../v1/models`. Azure lists models per resource rather than per deployment.
+fn provider_models_url(chat_url: &str) -> Result<String, String> {
+    let mut url = Url::parse(chat_url).map_err(|e| format!("Invalid provider URL: {}", e))?;
+    let path = url.path().trim_end_matches('/').to_string();
+    let base = match path.find("/deployments/") {
+        Some(index) => &path[..index],
+        None => CHAT_PATH_SUFFIXES
+            .iter()
+            .find_map(|suffix| path.strip_suffix(suffix))
+            .unwrap_or(&path),
+    };
+    url.set_path(&format!("{}/models", base));
+    Ok(url.to_string())
+}
+
+// Lists the models the selected provider offers. Uses the same auth as chat: the local
+// `models` override, then the `chat` one, then whatever the server configured.
+#[tauri::command]
+pub async fn fetch_provider_models(app: AppHandle) -> Result<Vec<String>, String> {
+    let (_, _, selected_model) = get_stored_credentials(&app).await?;
+    let (provider, model) = selected_model.as_ref().map_or((None, None), |m| {
+        (Some(m.provider.clone()), Some(m.model.clone()))
+    });
+    let api_config = fetch_api_response_config(&app, provider, model).await?;
+    let url = match api_config.models_url.as_deref() {
+        Some(url) => url.to_string(),
+        None => provider_models_url(&api_config.url)?,
+    };
+
+    let overrides = load_auth_overrides(&app);
+    let auth = resolve_endpoint_auth(
+        overrides.models.as_ref().or(overrides.chat.as_ref()),
+        api_config.auth.as_ref(),
+    );
+    let client = reqwest::Client::new();
+    let request = auth
+        .apply(client.get(&url), &api_config.user_token)
+        .build()
+        .map_err(|e| format!("Failed to make provider models request: {}", e))?;
+    let mut inspector_call = InspectorCall::begin(&app, CallKind::Models, Some(&request), None);
+
+    let response = client.execute(request).await.map_err(|e| {
+        inspector_call.set_error(e.to_string());
+        format!(
+            "Failed to make provider models request: {}",
+            e.without_url()
+        )
+    })?;
+    let status = response.status();
+    inspector_call.set_status(status.as_u16());
+    let body_text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read provider models response: {}", e))?;
+    inspector_call.set_response_body(&body_text);
+    if !status.is_success() {
+        return Err(format!("Provider error ({}): {}", status, body_text));
+    }
+
+    let models: ProviderModelsResponse = serde_json::from_str(&body_text)
+        .map_err(|e| format!("Failed to parse provider models response: {}", e))?;
+    let mut ids: Vec<String> = models.data.into_iter().map(|model| model.id).collect();
+    ids.sort();
+    Ok(ids)
+}
+
 // Create System Prompt API Command
 #[tauri::command]
 pub async fn create_system_prompt(
@@ -1005,3 +1911,115 @@ pub async fn get_activity(_app: AppHandle) -> Result<serde_json::Value, String>
         "total_tokens_used": 0
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provider_models_url_sits_next_to_the_chat_endpoint() {
+        let cases = [
+            (
+                "https://api.openai.com/v1/chat/completions",
+                "https://api.openai.com/v1/models",
+            ),
+            (
+                "https://api.anthropic.com/v1/messages",
+                "https://api.anthropic.com/v1/models",
+            ),
+            (
+                "https://gateway.example.com/openai/v1/responses/",
+                "https://gateway.example.com/openai/v1/models",
+            ),
+            (
+                "https://res.openai.azure.com/openai/deployments/gpt4o/chat/completions?api-version=2024-06-01",
+                "https://res.openai.azure.com/openai/models?api-version=2024-06-01",
+            ),
+        ];
+        for (chat, models) in cases {
+            assert_eq!(provider_models_url(chat).unwrap(), models);
+        }
+        assert!(provider_models_url("not a url").is_err());
+    }
+
+    #[test]
+    fn chunk_transcripts_shift_to_recording_time() {
+        let chunk = |offset_ms, duration_ms| AudioChunk {
+            offset_ms,
+            duration_ms,
+            bytes: Vec::new(),
+        };
+        let transcript = |text: &str, segments: Vec<TranscriptSegment>| Transcript {
+            text: text.to_string(),
+            language: None,
+            avg_logprob: None,
+            segments,
+        };
+
+        let with_segments = transcript(
+            "second part",
+            vec![TranscriptSegment {
+                start_ms: 500,
+                end_ms: 1500,
+                text: "second part".to_string(),
+            }],
+        )
+        .shifted(&chunk(300_000, 2_000));
+        assert_eq!(with_segments.segments[0].start_ms, 300_500);
+        assert_eq!(with_segments.segments[0].end_ms, 301_500);
+
+        // Without provider segments the whole chunk becomes one segment
+        let without = transcript(" third part ", Vec::new()).shifted(&chunk(600_000, 30_000));
+        assert_eq!(without.segments.len(), 1);
+        assert_eq!(without.segments[0].start_ms, 600_000);
+        assert_eq!(without.segments[0].end_ms, 630_000);
+        assert_eq!(without.segments[0].text, "third part");
+    }
+
+    #[test]
+    fn cache_model_follows_the_transcription_endpoints() {
+        let config = |extra: serde_json::Value| -> UserAudioConfig {
+            let mut value = serde_json::json!({
+                "url": "https://api.example.com/v1/audio/transcriptions",
+                "model": "whisper-1",
+                "user_token": "token",
+            });
+            value
+                .as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            serde_json::from_value(value).unwrap()
+        };
+
+        let base = config(serde_json::json!({}));
+        assert_eq!(
+            base.cache_model(),
+            "whisper-1@https://api.example.com/v1/audio/transcriptions"
+        );
+
+        // A different model, fallback or language route may give a different transcript
+        let keys = [
+            base.cache_model(),
+            config(serde_json::json!({ "model": "gpt-4o-transcribe" })).cache_model(),
+            config(serde_json::json!({ "fallback_url": "https://fallback.example.com" }))
+                .cache_model(),
+            config(serde_json::json!({
+                "language_routes": [{ "language": "de", "model": "whisper-de" }]
+            }))
+            .cache_model(),
+            config(serde_json::json!({
+                "language_routes": [{ "language": "fr", "model": "whisper-de" }]
+            }))
+            .cache_model(),
+        ];
+        for (i, key) in keys.iter().enumerate() {
+            assert!(!keys[i + 1..].contains(key), "duplicate key {}", key);
+        }
+
+        // The token isn't part of the key
+        assert_eq!(
+            config(serde_json::json!({ "user_token": "rotated" })).cache_model(),
+            base.cache_model()
+        );
+    }
+}
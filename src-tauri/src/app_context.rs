@@ -0,0 +1,388 @@
+// Context about the application the user is working in (frontmost window, optionally the
+// selected text), attached to chat requests as a system note
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use xcap::Window;
+
+const MAX_SELECTION_CHARS: usize = 2000;
+
+const IDE_APPS: &[&str] = &[
+    "code",
+    "cursor",
+    "intellij",
+    "pycharm",
+    "webstorm",
+    "goland",
+    "rustrover",
+    "clion",
+    "android studio",
+    "xcode",
+    "sublime",
+    "zed",
+    "vim",
+    "emacs",
+];
+const BROWSER_APPS: &[&str] = &[
+    "chrome", "chromium", "firefox", "safari", "edge", "brave", "opera", "vivaldi",
+];
+const MEETING_APPS: &[&str] = &[
+    "zoom", "teams", "meet", "webex", "slack", "discord", "skype",
+];
+const TERMINAL_APPS: &[&str] = &[
+    "terminal",
+    "iterm",
+    "warp",
+    "alacritty",
+    "kitty",
+    "wezterm",
+    "konsole",
+    "powershell",
+    "cmd.exe",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppKind {
+    Ide,
+    Browser,
+    Meeting,
+    Terminal,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveAppContext {
+    pub app_name: String,
+    pub window_title: String,
+    pub kind: AppKind,
+    pub selected_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppContextConfig {
+    // Attach the context to chat requests that don't say otherwise
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub include_selection: bool,
+}
+
+impl ActiveAppContext {
+    pub fn system_note(&self) -> String {
+        let kind = match self.kind {
+            AppKind::Ide => "code editor / IDE",
+            AppKind::Browser => "web browser",
+            AppKind::Meeting => "video call / meeting app",
+            AppKind::Terminal => "terminal",
+            AppKind::Other => "other application",
+        };
+
+        let mut note = format!(
+            "Context about what the user is currently looking at (collected automatically; \
+             use it to tailor the answer and don't mention it unless it's relevant):\n\
+             - Application: {} ({})\n\
+             - Window title: {}",
+            self.app_name, kind, self.window_title
+        );
+        if let Some(selection) = &self.selected_text {
+            note.push_str(&format!(
+                "\n- Selected text:\n\"\"\"\n{}\n\"\"\"",
+                selection
+            ));
+        }
+        note
+    }
+}
+
+fn classify_app(app_name: &str, window_title: &str) -> AppKind {
+    let app_name = app_name.to_lowercase();
+    let title = window_title.to_lowercase();
+    let matches = |names: &[&str]| names.iter().any(|name| app_name.contains(name));
+
+    // Browser-based calls ("Meet - abc-defg-hij") count as meetings
+    if matches(MEETING_APPS) || title.starts_with("meet -") || title.contains("zoom meeting") {
+        AppKind::Meeting
+    } else if matches(IDE_APPS) {
+        AppKind::Ide
+    } else if matches(BROWSER_APPS) {
+        AppKind::Browser
+    } else if matches(TERMINAL_APPS) {
+        AppKind::Terminal
+    } else {
+        AppKind::Other
+    }
+}
+
+// X11 window ids, front to back: the active window, then the rest of the stacking order.
+// xcap lists X11 windows from `_NET_CLIENT_LIST`, which is in mapping order.
+#[cfg(target_os = "linux")]
+fn window_order() -> Option<Vec<u32>> {
+    use xcb::x::{self, ATOM_NONE};
+    use xcb::Xid;
+
+    let (conn, screen_num) = xcb::Connection::connect(None).ok()?;
+    let root = conn.get_setup().roots().nth(screen_num as usize)?.root();
+    let property = |name: &str| -> Vec<u32> {
+        let atom = conn
+            .wait_for_reply(conn.send_request(&x::InternAtom {
+                only_if_exists: true,
+                name: name.as_bytes(),
+            }))
+            .map(|reply| reply.atom())
+            .unwrap_or(ATOM_NONE);
+        if atom == ATOM_NONE {
+            return Vec::new();
+        }
+        conn.wait_for_reply(conn.send_request(&x::GetProperty {
+            delete: false,
+            window: root,
+            property: atom,
+            r#type: ATOM_NONE,
+            long_offset: 0,
+            long_length: 1024,
+        }))
+        .map(|reply| {
+            reply
+                .value::<x::Window>()
+                .iter()
+                .map(|window| window.resource_id())
+                .collect()
+        })
+        .unwrap_or_default()
+    };
+
+    // Stacking order is bottom to top
+    let mut order = property("_NET_ACTIVE_WINDOW");
+    order.extend(property("_NET_CLIENT_LIST_STACKING").into_iter().rev());
+    (!order.is_empty()).then_some(order)
+}
+
+// xcap already lists windows front to back on macOS (CGWindowList) and Windows (EnumWindows)
+#[cfg(not(target_os = "linux"))]
+fn window_order() -> Option<Vec<u32>> {
+    None
+}
+
+// Windows as (id, app name, title). Puts them in `order` when there is one (ids missing
+// from it go last), then picks the first one that isn't ours: the app the user was
+// working in before focusing the overlay.
+fn pick_frontmost(
+    mut windows: Vec<(u32, String, String)>,
+    order: Option<&[u32]>,
+    own_name: &str,
+) -> Option<(String, String)> {
+    if let Some(order) = order {
+        windows.sort_by_key(|(id, _, _)| {
+            order
+                .iter()
+                .position(|ordered| ordered == id)
+                .unwrap_or(usize::MAX)
+        });
+    }
+
+    windows
+        .into_iter()
+        .find(|(_, app_name, title)| {
+            !app_name.is_empty() && !title.is_empty() && !app_name.eq_ignore_ascii_case(own_name)
+        })
+        .map(|(_, app_name, title)| (app_name, title))
+}
+
+fn frontmost_window(own_name: &str) -> Result<(String, String), String> {
+    let windows = Window::all().map_err(|e| format!("Failed to list windows: {}", e))?;
+    let windows = windows
+        .iter()
+        .filter(|window| !window.is_minimized() && window.width() > 1 && window.height() > 1)
+        .map(|window| {
+            (
+                window.id(),
+                window.app_name().trim().to_string(),
+                window.title().trim().to_string(),
+            )
+        })
+        .collect();
+
+    pick_frontmost(windows, window_order().as_deref(), own_name)
+        .ok_or_else(|| "No active application window found".to_string())
+}
+
+#[cfg(target_os = "macos")]
+async fn read_selected_text(app: &AppHandle) -> Option<String> {
+    use tauri_plugin_shell::ShellExt;
+
+    // Needs the accessibility permission the app already asks for
+    const SCRIPT: &str = "tell application \"System Events\"\n\
+        set frontApp to first application process whose frontmost is true\n\
+        set focusedElement to value of attribute \"AXFocusedUIElement\" of frontApp\n\
+        return value of attribute \"AXSelectedText\" of focusedElement\n\
+        end tell";
+
+    let output = app
+        .shell()
+        .command("osascript")
+        .args(["-e", SCRIPT])
+        .output()
+        .await
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(target_os = "linux")]
+async fn read_selected_text(app: &AppHandle) -> Option<String> {
+    use tauri_plugin_shell::ShellExt;
+
+    // The X11/Wayland primary selection holds the currently highlighted text
+    let commands: [(&str, &[&str]); 2] = [
+        ("wl-paste", &["--primary", "--no-newline"]),
+        ("xclip", &["-o", "-selection", "primary"]),
+    ];
+    for (program, args) in commands {
+        if let Ok(output) = app.shell().command(program).args(args).output().await {
+            if output.status.success() {
+                return Some(String::from_utf8_lossy(&output.stdout).to_string());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+async fn read_selected_text(_app: &AppHandle) -> Option<String> {
+    None
+}
+
+pub async fn collect_app_context(
+    app: &AppHandle,
+    include_selection: bool,
+) -> Result<ActiveAppContext, String> {
+    let own_name = app.package_info().name.clone();
+    let (app_name, window_title) = tokio::task::spawn_blocking(move || frontmost_window(&own_name))
+        .await
+        .map_err(|e| format!("Failed to read active window: {}", e))??;
+
+    let selected_text = if include_selection {
+        read_selected_text(app)
+            .await
+            .map(|text| {
+                text.trim()
+                    .chars()
+                    .take(MAX_SELECTION_CHARS)
+                    .collect::<String>()
+            })
+            .filter(|text| !text.is_empty())
+    } else {
+        None
+    };
+
+    Ok(ActiveAppContext {
+        kind: classify_app(&app_name, &window_title),
+        app_name,
+        window_title,
+        selected_text,
+    })
+}
+
+fn get_app_context_config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+    Ok(app_data_dir.join("app_context.json"))
+}
+
+pub fn load_app_context_config(app: &AppHandle) -> AppContextConfig {
+    let Ok(path) = get_app_context_config_path(app) else {
+        return AppContextConfig::default();
+    };
+
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub async fn get_active_app_context(
+    app: AppHandle,
+    include_selection: Option<bool>,
+) -> Result<ActiveAppContext, String> {
+    let include_selection =
+        include_selection.unwrap_or_else(|| load_app_context_config(&app).include_selection);
+    collect_app_context(&app, include_selection).await
+}
+
+#[tauri::command]
+pub fn get_app_context_config(app: AppHandle) -> Result<AppContextConfig, String> {
+    Ok(load_app_context_config(&app))
+}
+
+#[tauri::command]
+pub fn save_app_context_config(app: AppHandle, config: AppContextConfig) -> Result<(), String> {
+    let path = get_app_context_config_path(&app)?;
+    let content = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize app context config: {}", e))?;
+
+    fs::write(&path, content).map_err(|e| format!("Failed to write app context config: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn windows(entries: &[(u32, &str, &str)]) -> Vec<(u32, String, String)> {
+        entries
+            .iter()
+            .map(|(id, app_name, title)| (*id, app_name.to_string(), title.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn keeps_the_listed_order_without_a_window_order() {
+        let listed = windows(&[
+            (1, "Pluely", "Overlay"),
+            (2, "", "Desktop"),
+            (3, "Code", "main.rs"),
+            (4, "Firefox", "Docs"),
+        ]);
+        assert_eq!(
+            pick_frontmost(listed, None, "pluely"),
+            Some(("Code".to_string(), "main.rs".to_string()))
+        );
+    }
+
+    #[test]
+    fn follows_the_window_order_when_there_is_one() {
+        // Mapping order on X11: the oldest window comes first
+        let listed = windows(&[
+            (1, "Code", "main.rs"),
+            (2, "Firefox", "Docs"),
+            (3, "Pluely", "Overlay"),
+            (4, "Slack", "general"),
+        ]);
+
+        // Our overlay is active, then the stacking order top to bottom
+        assert_eq!(
+            pick_frontmost(listed.clone(), Some(&[3, 2, 4, 1]), "Pluely").map(|w| w.0),
+            Some("Firefox".to_string())
+        );
+        assert_eq!(
+            pick_frontmost(listed.clone(), Some(&[3, 4, 2, 1]), "Pluely").map(|w| w.0),
+            Some("Slack".to_string())
+        );
+
+        // Windows the order doesn't know about go last
+        assert_eq!(
+            pick_frontmost(listed, Some(&[3]), "Pluely").map(|w| w.0),
+            Some("Code".to_string())
+        );
+    }
+}
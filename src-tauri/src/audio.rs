@@ -1,22 +1,318 @@
-use tauri::command;
+// Pluely microphone input and stream, built on cpal. Mirrors `SpeakerInput`/`SpeakerStream`
+// so the user's own voice goes through the same VAD and WAV pipeline as system audio.
+use crate::speaker::{
+    start_capture, stop_capture, AudioDevice, AudioDeviceKind, CaptureSource, VadConfig,
+};
+use anyhow::{anyhow, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SampleFormat, SizedSample};
+use futures_util::Stream;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Waker};
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+use tracing::{error, warn};
 
-#[command]
-pub async fn check_system_audio_access() -> Result<bool, String> {
-    // For now, return true to indicate access is available
-    // This can be enhanced later with platform-specific permission checks
-    Ok(true)
+const MAX_BUFFER_SAMPLES: usize = 131072; // matching the speaker backends
+
+// For the microphone, device IDs are cpal device names
+fn find_input_device(device_id: Option<&str>) -> Result<cpal::Device> {
+    let host = cpal::default_host();
+    match device_id {
+        Some(id) => host
+            .input_devices()?
+            .find(|device| device.name().map(|name| name == id).unwrap_or(false))
+            .ok_or_else(|| anyhow!("Microphone not found: {}", id)),
+        None => host
+            .default_input_device()
+            .ok_or_else(|| anyhow!("No default microphone available")),
+    }
+}
+
+// Capture devices with their default format. IDs are cpal device names, as accepted by
+// `MicrophoneInput::new`.
+pub fn list_input_devices() -> Result<Vec<AudioDevice>> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+    let mut devices = Vec::new();
+
+    for device in host.input_devices()? {
+        let Ok(name) = device.name() else {
+            continue;
+        };
+        let (channels, sample_rate) = device
+            .default_input_config()
+            .map(|config| (config.channels(), config.sample_rate().0))
+            .unwrap_or((0, 0));
+
+        devices.push(AudioDevice {
+            id: name.clone(),
+            is_default: default_name.as_ref() == Some(&name),
+            name,
+            kind: AudioDeviceKind::Input,
+            channels,
+            sample_rate,
+        });
+    }
+
+    Ok(devices)
+}
+
+pub struct MicrophoneInput {
+    device_id: Option<String>,
+}
+
+impl MicrophoneInput {
+    // Uses the default input device when `device_id` is `None`
+    pub fn new(device_id: Option<String>) -> Result<Self> {
+        // Resolve the device up front so a bad ID fails here instead of in the stream
+        find_input_device(device_id.as_deref())?;
+        Ok(Self { device_id })
+    }
+
+    // Starts the audio stream. cpal streams can't move between threads on every platform,
+    // so the stream lives on its own thread, like the Linux speaker backend. Fails if the
+    // stream can't be built or started.
+    pub fn stream(self) -> Result<MicrophoneStream> {
+        let sample_queue = Arc::new(Mutex::new(VecDeque::new()));
+        let waker_state = Arc::new(Mutex::new(WakerState {
+            waker: None,
+            has_data: false,
+            shutdown: false,
+        }));
+        let (init_tx, init_rx) = std::sync::mpsc::channel();
+
+        let queue_clone = sample_queue.clone();
+        let waker_clone = waker_state.clone();
+        let device_id = self.device_id;
+
+        let capture_thread = thread::spawn(move || {
+            MicrophoneStream::capture_loop(queue_clone, waker_clone, device_id.as_deref(), init_tx)
+        });
+
+        let sample_rate = match init_rx.recv() {
+            Ok(Ok(sr)) => sr,
+            Ok(Err(e)) => {
+                let _ = capture_thread.join();
+                return Err(anyhow!("Microphone initialization failed: {}", e));
+            }
+            Err(e) => {
+                let _ = capture_thread.join();
+                return Err(anyhow!("Failed to receive microphone init signal: {}", e));
+            }
+        };
+
+        Ok(MicrophoneStream {
+            sample_queue,
+            waker_state,
+            capture_thread: Some(capture_thread),
+            sample_rate,
+        })
+    }
+}
+
+struct WakerState {
+    waker: Option<Waker>,
+    has_data: bool,
+    shutdown: bool,
+}
+
+fn mark_shutdown(waker_state: &Mutex<WakerState>) {
+    let mut state = waker_state.lock().unwrap();
+    state.shutdown = true;
+    if let Some(waker) = state.waker.take() {
+        drop(state);
+        waker.wake();
+    }
+}
+
+// Stream of mono f32 samples from the microphone
+pub struct MicrophoneStream {
+    sample_queue: Arc<Mutex<VecDeque<f32>>>,
+    waker_state: Arc<Mutex<WakerState>>,
+    capture_thread: Option<thread::JoinHandle<()>>,
+    sample_rate: u32,
+}
+
+impl MicrophoneStream {
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn capture_loop(
+        sample_queue: Arc<Mutex<VecDeque<f32>>>,
+        waker_state: Arc<Mutex<WakerState>>,
+        device_id: Option<&str>,
+        init_tx: std::sync::mpsc::Sender<Result<u32>>,
+    ) {
+        let init_result: Result<(cpal::Stream, u32)> = (|| {
+            let device = find_input_device(device_id)?;
+            let config = device.default_input_config()?;
+            let sample_rate = config.sample_rate().0;
+            let sample_format = config.sample_format();
+            let config: cpal::StreamConfig = config.into();
+
+            let queue = sample_queue.clone();
+            let waker = waker_state.clone();
+            let stream = match sample_format {
+                SampleFormat::F32 => build_stream::<f32>(&device, &config, queue, waker),
+                SampleFormat::I16 => build_stream::<i16>(&device, &config, queue, waker),
+                SampleFormat::U16 => build_stream::<u16>(&device, &config, queue, waker),
+                SampleFormat::I32 => build_stream::<i32>(&device, &config, queue, waker),
+                SampleFormat::I8 => build_stream::<i8>(&device, &config, queue, waker),
+                SampleFormat::U8 => build_stream::<u8>(&device, &config, queue, waker),
+                format => Err(anyhow!("Unsupported microphone sample format: {}", format)),
+            }?;
+            stream.play()?;
+
+            Ok((stream, sample_rate))
+        })();
+
+        match init_result {
+            Ok((stream, sample_rate)) => {
+                let _ = init_tx.send(Ok(sample_rate));
+
+                // cpal delivers samples on its own callback thread; this one only keeps the
+                // stream alive until shutdown
+                while !waker_state.lock().unwrap().shutdown {
+                    thread::sleep(Duration::from_millis(50));
+                }
+                drop(stream);
+            }
+            Err(e) => {
+                let _ = init_tx.send(Err(e));
+            }
+        }
+    }
+}
+
+// Builds an input stream that downmixes every frame to mono f32
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    sample_queue: Arc<Mutex<VecDeque<f32>>>,
+    waker_state: Arc<Mutex<WakerState>>,
+) -> Result<cpal::Stream>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let channels = config.channels.max(1) as usize;
+    let error_waker = waker_state.clone();
+
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            let dropped = {
+                let mut queue = sample_queue.lock().unwrap();
+                queue.extend(data.chunks(channels).map(|frame| {
+                    frame.iter().map(|&s| s.to_sample::<f32>()).sum::<f32>() / frame.len() as f32
+                }));
+
+                // If buffer exceeds maximum, drop oldest samples
+                if queue.len() > MAX_BUFFER_SAMPLES {
+                    let to_drop = queue.len() - MAX_BUFFER_SAMPLES;
+                    queue.drain(0..to_drop);
+                    to_drop
+                } else {
+                    0
+                }
+            };
+
+            if dropped > 0 {
+                warn!("Microphone buffer overflow - dropped {} samples", dropped);
+            }
+
+            // Wake up consumer
+            let mut state = waker_state.lock().unwrap();
+            if !state.has_data {
+                state.has_data = true;
+                if let Some(waker) = state.waker.take() {
+                    drop(state);
+                    waker.wake();
+                }
+            }
+        },
+        // Errors here mean the device went away; end the stream so the capture task stops
+        move |e| {
+            error!("Microphone stream error: {}", e);
+            mark_shutdown(&error_waker);
+        },
+        None,
+    )?;
+
+    Ok(stream)
+}
+
+impl Drop for MicrophoneStream {
+    fn drop(&mut self) {
+        mark_shutdown(&self.waker_state);
+        if let Some(thread) = self.capture_thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Stream for MicrophoneStream {
+    type Item = f32;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut queue = self.sample_queue.lock().unwrap();
+        if let Some(sample) = queue.pop_front() {
+            return Poll::Ready(Some(sample));
+        }
+
+        let mut state = self.waker_state.lock().unwrap();
+        if state.shutdown {
+            return Poll::Ready(None);
+        }
+
+        state.has_data = false;
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+// Captures the microphone through the same VAD pipeline as system audio. Utterances are
+// labelled "me" and can be captured alongside system audio ("them").
+#[tauri::command]
+pub async fn start_microphone_capture(
+    app: AppHandle,
+    vad_config: Option<VadConfig>,
+    device_id: Option<String>,
+) -> Result<(), String> {
+    start_capture(&app, CaptureSource::Me, vad_config, move || {
+        let input = MicrophoneInput::new(device_id).map_err(|e| {
+            error!("Failed to create microphone input: {}", e);
+            format!("Failed to access microphone: {}", e)
+        })?;
+
+        let stream = input.stream().map_err(|e| {
+            error!("Failed to start microphone stream: {}", e);
+            format!("Failed to start microphone: {}", e)
+        })?;
+        let sr = stream.sample_rate();
+        Ok((stream, sr))
+    })
 }
 
-#[command]
-pub async fn start_system_audio_capture() -> Result<(), String> {
-    // Start audio capture
-    // Implementation will depend on your audio capture logic
-    Ok(())
+#[tauri::command]
+pub async fn stop_microphone_capture(app: AppHandle) -> Result<(), String> {
+    stop_capture(&app, CaptureSource::Me).await
 }
 
-#[command]
-pub async fn stop_system_audio_capture() -> Result<(), String> {
-    // Stop audio capture
-    // Implementation will depend on your audio capture logic
-    Ok(())
+#[tauri::command]
+pub fn check_microphone_access(_app: AppHandle) -> Result<bool, String> {
+    match MicrophoneInput::new(None) {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            error!("Microphone access check failed: {}", e);
+            Ok(false)
+        }
+    }
 }
@@ -0,0 +1,325 @@
+// Provider authentication schemes for chat, transcription and model listing requests
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const DEFAULT_ANTHROPIC_VERSION: &str = "2023-06-01";
+
+// How the provider token is attached to a request
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuthStyle {
+    // `Authorization: Bearer <token>`
+    #[default]
+    Bearer,
+    // Azure OpenAI: `api-key: <token>` plus the `api-version` query parameter
+    Azure {
+        #[serde(default)]
+        api_version: Option<String>,
+    },
+    // Anthropic: `x-api-key: <token>` plus the `anthropic-version` header
+    Anthropic {
+        #[serde(default)]
+        version: Option<String>,
+    },
+    // Custom header, e.g. `X-Gateway-Key: Token <token>`
+    Header {
+        name: String,
+        #[serde(default)]
+        prefix: Option<String>,
+    },
+    // Token passed as a query parameter
+    Query {
+        name: String,
+    },
+    // No token at all (headers/query below still apply)
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: String,
+}
+
+// Auth settings for a single endpoint
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EndpointAuth {
+    #[serde(default)]
+    pub style: AuthStyle,
+    #[serde(default)]
+    pub headers: Vec<KeyValue>,
+    #[serde(default)]
+    pub query: Vec<KeyValue>,
+}
+
+impl EndpointAuth {
+    // Attaches the token, extra headers and query parameters to the request
+    pub fn apply(&self, request: RequestBuilder, token: &str) -> RequestBuilder {
+        let mut request = match &self.style {
+            AuthStyle::Bearer => request.bearer_auth(token),
+            AuthStyle::Azure { api_version } => {
+                let request = request.header("api-key", token);
+                match api_version.as_deref().filter(|v| !v.trim().is_empty()) {
+                    Some(version) => request.query(&[("api-version", version)]),
+                    None => request,
+                }
+            }
+            AuthStyle::Anthropic { version } => request.header("x-api-key", token).header(
+                "anthropic-version",
+                version
+                    .as_deref()
+                    .filter(|v| !v.trim().is_empty())
+                    .unwrap_or(DEFAULT_ANTHROPIC_VERSION),
+            ),
+            AuthStyle::Header { name, prefix } => {
+                let value = match prefix.as_deref().filter(|p| !p.is_empty()) {
+                    Some(prefix) => format!("{} {}", prefix, token),
+                    None => token.to_string(),
+                };
+                request.header(name.as_str(), value)
+            }
+            AuthStyle::Query { name } => request.query(&[(name.as_str(), token)]),
+            AuthStyle::None => request,
+        };
+
+        for header in &self.headers {
+            let key = header.key.trim();
+            if !key.is_empty() {
+                request = request.header(key, header.value.as_str());
+            }
+        }
+
+        let query: Vec<(&str, &str)> = self
+            .query
+            .iter()
+            .filter(|param| !param.key.trim().is_empty())
+            .map(|param| (param.key.trim(), param.value.as_str()))
+            .collect();
+        if !query.is_empty() {
+            request = request.query(&query);
+        }
+
+        request
+    }
+}
+
+// Local per-endpoint overrides, stored next to the other app data files.
+// These win over whatever the server delivers in the API config. Only provider
+// endpoints can be overridden; Pluely's own API always uses the app's key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthOverrides {
+    #[serde(default)]
+    pub chat: Option<EndpointAuth>,
+    #[serde(default)]
+    pub transcription: Option<EndpointAuth>,
+    // Provider model listing; falls back to `chat`, since both go to the same provider
+    #[serde(default)]
+    pub models: Option<EndpointAuth>,
+}
+
+fn get_auth_overrides_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+    Ok(app_data_dir.join("auth_overrides.json"))
+}
+
+pub fn load_auth_overrides(app: &AppHandle) -> AuthOverrides {
+    let Ok(path) = get_auth_overrides_path(app) else {
+        return AuthOverrides::default();
+    };
+
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// Picks the local override first, then the server-provided auth, then plain Bearer
+pub fn resolve_endpoint_auth(
+    local: Option<&EndpointAuth>,
+    remote: Option<&EndpointAuth>,
+) -> EndpointAuth {
+    local.or(remote).cloned().unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn get_auth_overrides(app: AppHandle) -> Result<AuthOverrides, String> {
+    Ok(load_auth_overrides(&app))
+}
+
+#[tauri::command]
+pub fn save_auth_overrides(app: AppHandle, overrides: AuthOverrides) -> Result<(), String> {
+    let path = get_auth_overrides_path(&app)?;
+    let content = serde_json::to_string_pretty(&overrides)
+        .map_err(|e| format!("Failed to serialize auth overrides: {}", e))?;
+
+    fs::write(&path, content).map_err(|e| format!("Failed to write auth overrides: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(auth: &EndpointAuth) -> reqwest::Request {
+        auth.apply(
+            reqwest::Client::new().get("https://provider.test/v1/models"),
+            "secret",
+        )
+        .build()
+        .unwrap()
+    }
+
+    fn header(request: &reqwest::Request, name: &str) -> Option<String> {
+        request
+            .headers()
+            .get(name)
+            .map(|value| value.to_str().unwrap().to_string())
+    }
+
+    fn query(request: &reqwest::Request) -> Vec<(String, String)> {
+        request
+            .url()
+            .query_pairs()
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect()
+    }
+
+    fn with_style(style: AuthStyle) -> EndpointAuth {
+        EndpointAuth {
+            style,
+            ..Default::default()
+        }
+    }
+
+    fn pair(key: &str, value: &str) -> (String, String) {
+        (key.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn bearer_sets_the_authorization_header() {
+        let request = build(&with_style(AuthStyle::Bearer));
+        assert_eq!(
+            header(&request, "authorization").as_deref(),
+            Some("Bearer secret")
+        );
+        assert!(query(&request).is_empty());
+    }
+
+    #[test]
+    fn azure_uses_api_key_and_version() {
+        let request = build(&with_style(AuthStyle::Azure {
+            api_version: Some("2024-06-01".to_string()),
+        }));
+        assert_eq!(header(&request, "api-key").as_deref(), Some("secret"));
+        assert_eq!(header(&request, "authorization"), None);
+        assert_eq!(query(&request), vec![pair("api-version", "2024-06-01")]);
+
+        let request = build(&with_style(AuthStyle::Azure {
+            api_version: Some(" ".to_string()),
+        }));
+        assert!(query(&request).is_empty());
+    }
+
+    #[test]
+    fn anthropic_sends_key_and_version() {
+        let request = build(&with_style(AuthStyle::Anthropic { version: None }));
+        assert_eq!(header(&request, "x-api-key").as_deref(), Some("secret"));
+        assert_eq!(
+            header(&request, "anthropic-version").as_deref(),
+            Some(DEFAULT_ANTHROPIC_VERSION)
+        );
+
+        let request = build(&with_style(AuthStyle::Anthropic {
+            version: Some("2024-01-01".to_string()),
+        }));
+        assert_eq!(
+            header(&request, "anthropic-version").as_deref(),
+            Some("2024-01-01")
+        );
+    }
+
+    #[test]
+    fn custom_header_with_and_without_prefix() {
+        let request = build(&with_style(AuthStyle::Header {
+            name: "X-Gateway-Key".to_string(),
+            prefix: Some("Token".to_string()),
+        }));
+        assert_eq!(
+            header(&request, "x-gateway-key").as_deref(),
+            Some("Token secret")
+        );
+        assert_eq!(header(&request, "authorization"), None);
+
+        let request = build(&with_style(AuthStyle::Header {
+            name: "X-Gateway-Key".to_string(),
+            prefix: None,
+        }));
+        assert_eq!(header(&request, "x-gateway-key").as_deref(), Some("secret"));
+    }
+
+    #[test]
+    fn query_style_puts_the_token_in_the_url() {
+        let request = build(&with_style(AuthStyle::Query {
+            name: "key".to_string(),
+        }));
+        assert_eq!(query(&request), vec![pair("key", "secret")]);
+        assert_eq!(header(&request, "authorization"), None);
+    }
+
+    #[test]
+    fn extra_headers_and_query_apply_without_a_token() {
+        let auth = EndpointAuth {
+            style: AuthStyle::None,
+            headers: vec![
+                KeyValue {
+                    key: "X-Org".to_string(),
+                    value: "acme".to_string(),
+                },
+                KeyValue {
+                    key: " ".to_string(),
+                    value: "ignored".to_string(),
+                },
+            ],
+            query: vec![
+                KeyValue {
+                    key: " region ".to_string(),
+                    value: "eu".to_string(),
+                },
+                KeyValue {
+                    key: "".to_string(),
+                    value: "ignored".to_string(),
+                },
+            ],
+        };
+        let request = build(&auth);
+        assert_eq!(header(&request, "x-org").as_deref(), Some("acme"));
+        assert_eq!(header(&request, "authorization"), None);
+        assert_eq!(query(&request), vec![pair("region", "eu")]);
+    }
+
+    #[test]
+    fn local_override_wins_over_remote() {
+        let local = with_style(AuthStyle::Query {
+            name: "key".to_string(),
+        });
+        let remote = with_style(AuthStyle::Anthropic { version: None });
+        assert_eq!(
+            resolve_endpoint_auth(Some(&local), Some(&remote)).style,
+            local.style
+        );
+        assert_eq!(
+            resolve_endpoint_auth(None, Some(&remote)).style,
+            remote.style
+        );
+        assert_eq!(resolve_endpoint_auth(None, None).style, AuthStyle::Bearer);
+    }
+}
@@ -0,0 +1,235 @@
+// Splits long WAV recordings at quiet points into chunks that fit provider upload limits
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::ops::Range;
+
+// Most transcription APIs reject uploads above 25 MB
+const MAX_CHUNK_BYTES: usize = 24 * 1024 * 1024;
+// Shorter chunks transcribe in parallel and stay under provider duration limits
+const MAX_CHUNK_SECS: usize = 300;
+// Splits are searched for in the last quarter of each chunk
+const SPLIT_SEARCH_RATIO: f32 = 0.25;
+const SILENCE_FRAME_MS: usize = 100;
+const WAV_HEADER_BYTES: usize = 44;
+
+pub struct AudioChunk {
+    pub offset_ms: u64,
+    pub duration_ms: u64,
+    pub bytes: Vec<u8>,
+}
+
+// Time-stamped piece of a transcript, relative to the start of the full recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+// Mean energy of one quiet-point candidate (interleaved samples)
+fn frame_energy(samples: &[i16]) -> f64 {
+    if samples.is_empty() {
+        return f64::MAX;
+    }
+    let sum: f64 = samples.iter().map(|&s| (s as f64) * (s as f64)).sum();
+    sum / samples.len() as f64
+}
+
+// Frame ranges of each chunk. Every chunk except the last ends at the quietest
+// `SILENCE_FRAME_MS` window near its maximum length, so words are not cut in half.
+fn split_points(
+    samples: &[i16],
+    channels: usize,
+    sample_rate: usize,
+    max_frames: usize,
+) -> Vec<Range<usize>> {
+    let total_frames = samples.len() / channels;
+    let window = (sample_rate * SILENCE_FRAME_MS / 1000).max(1);
+    let search = ((max_frames as f32 * SPLIT_SEARCH_RATIO) as usize).max(window);
+    let mut ranges = Vec::new();
+    let mut start = 0;
+
+    while total_frames - start > max_frames {
+        let limit = start + max_frames;
+        let mut best = limit;
+        let mut best_energy = f64::MAX;
+
+        let mut candidate = limit.saturating_sub(search).max(start + window);
+        while candidate + window <= limit {
+            let energy =
+                frame_energy(&samples[candidate * channels..(candidate + window) * channels]);
+            if energy < best_energy {
+                best_energy = energy;
+                best = candidate + window / 2;
+            }
+            candidate += window / 2 + 1;
+        }
+
+        ranges.push(start..best);
+        start = best;
+    }
+
+    ranges.push(start..total_frames);
+    ranges
+}
+
+fn encode_wav(spec: WavSpec, samples: &[i16]) -> Result<Vec<u8>, String> {
+    let mut cursor = Cursor::new(Vec::new());
+    let mut writer = WavWriter::new(&mut cursor, spec).map_err(|e| e.to_string())?;
+    for &sample in samples {
+        writer.write_sample(sample).map_err(|e| e.to_string())?;
+    }
+    writer.finalize().map_err(|e| e.to_string())?;
+    Ok(cursor.into_inner())
+}
+
+// Returns `None` when the audio is small enough to upload as is, or is not 16-bit PCM WAV
+// (other formats are passed through untouched)
+pub fn split_wav_for_upload(audio_bytes: &[u8]) -> Result<Option<Vec<AudioChunk>>, String> {
+    let Ok(reader) = WavReader::new(Cursor::new(audio_bytes)) else {
+        return Ok(None);
+    };
+    let spec = reader.spec();
+    if spec.sample_format != SampleFormat::Int || spec.bits_per_sample != 16 {
+        return Ok(None);
+    }
+
+    let channels = spec.channels.max(1) as usize;
+    let sample_rate = spec.sample_rate as usize;
+    let frame_bytes = channels * 2;
+    let max_frames =
+        ((MAX_CHUNK_BYTES - WAV_HEADER_BYTES) / frame_bytes).min(sample_rate * MAX_CHUNK_SECS);
+    if (reader.duration() as usize) <= max_frames {
+        return Ok(None);
+    }
+
+    let samples: Vec<i16> = reader
+        .into_samples::<i16>()
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Failed to read audio samples: {}", e))?;
+
+    let frames_to_ms = |frames: usize| (frames as u64 * 1000) / sample_rate as u64;
+    split_points(&samples, channels, sample_rate, max_frames)
+        .into_iter()
+        .map(|range| {
+            let bytes = encode_wav(spec, &samples[range.start * channels..range.end * channels])
+                .map_err(|e| format!("Failed to encode audio chunk: {}", e))?;
+            Ok(AudioChunk {
+                offset_ms: frames_to_ms(range.start),
+                duration_ms: frames_to_ms(range.end - range.start),
+                bytes,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()
+        .map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 1000;
+
+    fn spec(channels: u16) -> WavSpec {
+        WavSpec {
+            channels,
+            sample_rate: RATE,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        }
+    }
+
+    // Steady tone, so no point is quieter than another
+    fn tone(frames: usize, channels: usize) -> Vec<i16> {
+        (0..frames * channels)
+            .map(|i| {
+                let t = (i / channels) as f32 / RATE as f32;
+                ((2.0 * std::f32::consts::PI * 50.0 * t).sin() * 10_000.0) as i16
+            })
+            .collect()
+    }
+
+    fn decode(bytes: &[u8]) -> Vec<i16> {
+        WavReader::new(Cursor::new(bytes))
+            .unwrap()
+            .into_samples::<i16>()
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    // Chunks follow each other without gaps and decode back to the original samples
+    fn assert_stitches(chunks: &[AudioChunk], samples: &[i16], channels: usize) {
+        let mut expected_offset = 0;
+        let mut stitched = Vec::new();
+        for chunk in chunks {
+            assert_eq!(chunk.offset_ms, expected_offset);
+            expected_offset += chunk.duration_ms;
+
+            let chunk_samples = decode(&chunk.bytes);
+            assert_eq!(
+                chunk_samples.len() / channels,
+                chunk.duration_ms as usize * RATE as usize / 1000
+            );
+            assert!(chunk.bytes.len() <= MAX_CHUNK_BYTES);
+            assert!(chunk.duration_ms as usize <= MAX_CHUNK_SECS * 1000);
+            stitched.extend(chunk_samples);
+        }
+        assert_eq!(
+            expected_offset as usize,
+            samples.len() / channels * 1000 / RATE as usize
+        );
+        assert_eq!(stitched, samples);
+    }
+
+    #[test]
+    fn short_or_unsupported_audio_is_left_alone() {
+        let samples = tone(RATE as usize * 10, 1);
+        let wav = encode_wav(spec(1), &samples).unwrap();
+        assert!(split_wav_for_upload(&wav).unwrap().is_none());
+        assert!(split_wav_for_upload(b"not a wav file").unwrap().is_none());
+    }
+
+    #[test]
+    fn splits_long_audio_at_silence() {
+        let max_frames = MAX_CHUNK_SECS * RATE as usize;
+        let mut samples = tone(max_frames * 2 + RATE as usize * 30, 1);
+        // A one-second pause inside the search window of the first chunk
+        let pause = max_frames - RATE as usize * 20;
+        samples[pause..pause + RATE as usize].fill(0);
+
+        let wav = encode_wav(spec(1), &samples).unwrap();
+        let chunks = split_wav_for_upload(&wav).unwrap().unwrap();
+        assert_eq!(chunks.len(), 3);
+        let first_end = chunks[1].offset_ms as usize * RATE as usize / 1000;
+        assert!((pause..pause + RATE as usize).contains(&first_end));
+        assert_stitches(&chunks, &samples, 1);
+    }
+
+    #[test]
+    fn splits_without_silence_near_the_limit() {
+        let max_frames = MAX_CHUNK_SECS * RATE as usize;
+        let samples = tone(max_frames * 2 + RATE as usize * 30, 2);
+
+        let wav = encode_wav(spec(2), &samples).unwrap();
+        let chunks = split_wav_for_upload(&wav).unwrap().unwrap();
+        assert_eq!(chunks.len(), 3);
+        let search = (max_frames as f32 * SPLIT_SEARCH_RATIO) as u64 * 1000 / RATE as u64;
+        for chunk in &chunks[..2] {
+            assert!(chunk.duration_ms >= MAX_CHUNK_SECS as u64 * 1000 - search);
+        }
+        assert_stitches(&chunks, &samples, 2);
+    }
+
+    #[test]
+    fn split_points_cover_every_frame() {
+        let samples = tone(10_000, 1);
+        let ranges = split_points(&samples, 1, RATE as usize, 3_000);
+        assert_eq!(ranges.first().unwrap().start, 0);
+        assert_eq!(ranges.last().unwrap().end, 10_000);
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+        assert!(ranges.iter().all(|range| range.len() <= 3_000));
+    }
+}
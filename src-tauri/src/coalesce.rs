@@ -0,0 +1,113 @@
+// Batches streamed chat deltas so the webview receives fewer, larger events
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CoalesceConfig {
+    // Longest time a delta may wait before it is emitted. 0 disables coalescing.
+    pub flush_interval_ms: u64,
+    // A batch is emitted as soon as it holds at least this many characters
+    pub min_chars: usize,
+}
+
+impl Default for CoalesceConfig {
+    fn default() -> Self {
+        Self {
+            flush_interval_ms: 50,
+            min_chars: 32,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct CoalesceState {
+    config: Mutex<CoalesceConfig>,
+}
+
+pub fn load_coalesce_config(app: &AppHandle) -> CoalesceConfig {
+    let state = app.state::<CoalesceState>();
+    let config = match state.config.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    *config
+}
+
+// Time is passed in by the caller so recorded streams can be replayed deterministically
+pub struct ChunkCoalescer {
+    config: CoalesceConfig,
+    buffer: String,
+    buffered_chars: usize,
+    oldest: Option<Instant>,
+}
+
+impl ChunkCoalescer {
+    pub fn new(config: CoalesceConfig) -> Self {
+        Self {
+            config,
+            buffer: String::new(),
+            buffered_chars: 0,
+            oldest: None,
+        }
+    }
+
+    // Adds a delta; returns a batch when it is due
+    pub fn push(&mut self, delta: &str, now: Instant) -> Option<String> {
+        if delta.is_empty() {
+            return None;
+        }
+
+        self.buffer.push_str(delta);
+        self.buffered_chars += delta.chars().count();
+        let oldest = *self.oldest.get_or_insert(now);
+
+        let interval = Duration::from_millis(self.config.flush_interval_ms);
+        if self.buffered_chars >= self.config.min_chars || now.duration_since(oldest) >= interval {
+            self.flush()
+        } else {
+            None
+        }
+    }
+
+    // When the buffered batch must go out even if no more deltas arrive
+    pub fn deadline(&self) -> Option<Instant> {
+        self.oldest
+            .map(|oldest| oldest + Duration::from_millis(self.config.flush_interval_ms))
+    }
+
+    // Emits whatever is buffered (completion, errors, or an expired deadline)
+    pub fn flush(&mut self) -> Option<String> {
+        self.oldest = None;
+        self.buffered_chars = 0;
+        if self.buffer.is_empty() {
+            None
+        } else {
+            Some(std::mem::take(&mut self.buffer))
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_stream_coalesce_config(app: AppHandle) -> Result<CoalesceConfig, String> {
+    Ok(load_coalesce_config(&app))
+}
+
+#[tauri::command]
+pub fn update_stream_coalesce_config(app: AppHandle, config: CoalesceConfig) -> Result<(), String> {
+    if config.flush_interval_ms > 1000 {
+        return Err("Invalid flush_interval_ms: must be <= 1000".to_string());
+    }
+    if config.min_chars > 4096 {
+        return Err("Invalid min_chars: must be <= 4096".to_string());
+    }
+
+    let state = app.state::<CoalesceState>();
+    *state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to update stream config: {}", e))? = config;
+
+    Ok(())
+}
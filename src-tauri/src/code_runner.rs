@@ -0,0 +1,463 @@
+// Runs code snippets from answers in a sandboxed child process: the filesystem is read-only
+// apart from a temporary working directory, the home directory is hidden, and there is no
+// network, a memory cap (where the OS enforces one) and a timeout. Output is streamed as
+// events.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::ShellExt;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::mpsc;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+const MAX_TIMEOUT_SECS: u64 = 60;
+const MEMORY_LIMIT_MB: u64 = 512;
+// macOS doesn't enforce RLIMIT_DATA (or RLIMIT_AS), so runs there only get Node's heap cap
+// and report no memory limit
+const MEMORY_LIMIT_ENFORCED: bool = cfg!(not(target_os = "macos"));
+const MAX_CODE_BYTES: usize = 100 * 1024;
+// Runs printing more than this are killed, so runaway loops can't flood the UI
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+// Interpreters installed by version managers live in the otherwise hidden home directory
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+const TOOLCHAIN_DIRS: &[&str] = &[
+    ".pyenv",
+    ".nvm",
+    ".volta",
+    ".asdf",
+    ".local/share/mise",
+    ".local/share/fnm",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnippetLanguage {
+    #[serde(alias = "py", alias = "python3")]
+    Python,
+    #[serde(alias = "js", alias = "node")]
+    Javascript,
+    #[serde(alias = "sh", alias = "bash")]
+    Shell,
+}
+
+impl SnippetLanguage {
+    // Script file name and the interpreter command that runs it
+    fn program(self) -> (&'static str, Vec<String>) {
+        match self {
+            SnippetLanguage::Python => (
+                "snippet.py",
+                // Isolated mode ignores user site-packages and PYTHON* variables
+                vec![
+                    "python3".into(),
+                    "-I".into(),
+                    "-B".into(),
+                    "snippet.py".into(),
+                ],
+            ),
+            SnippetLanguage::Javascript => (
+                "snippet.js",
+                vec![
+                    "node".into(),
+                    // V8 reserves address space up front, so the heap is capped separately
+                    format!("--max-old-space-size={}", MEMORY_LIMIT_MB / 2),
+                    "snippet.js".into(),
+                ],
+            ),
+            SnippetLanguage::Shell => ("snippet.sh", vec!["sh".into(), "snippet.sh".into()]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeRunStatus {
+    Exited,
+    TimedOut,
+    Cancelled,
+    OutputLimit,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CodeRunOutput<'a> {
+    run_id: &'a str,
+    stream: &'static str,
+    data: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CodeRunFinished<'a> {
+    run_id: &'a str,
+    status: CodeRunStatus,
+    exit_code: Option<i32>,
+    duration_ms: u64,
+    // None when the platform can't cap the snippet's memory
+    memory_limit_mb: Option<u64>,
+}
+
+#[derive(Default)]
+pub struct CodeRunState {
+    // Process group of each running snippet
+    runs: Mutex<HashMap<String, u32>>,
+}
+
+fn take_run(app: &AppHandle, run_id: &str) -> Option<u32> {
+    app.state::<CodeRunState>()
+        .runs
+        .lock()
+        .ok()
+        .and_then(|mut runs| runs.remove(run_id))
+}
+
+// Kills the snippet and everything it started in the background
+#[cfg(unix)]
+fn kill_process_group(run_id: &str, process_group: u32) {
+    // SAFETY: kill has no memory-safety preconditions; a negative pid targets the group
+    if unsafe { libc::kill(-(process_group as libc::pid_t), libc::SIGKILL) } != 0 {
+        let error = std::io::Error::last_os_error();
+        // The group is already gone when every process in it has exited
+        if error.raw_os_error() != Some(libc::ESRCH) {
+            tracing::warn!("Failed to kill code run {}: {}", run_id, error);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(_run_id: &str, _process_group: u32) {}
+
+// Returns false if the run already finished or was killed elsewhere
+fn kill_run(app: &AppHandle, run_id: &str) -> bool {
+    let Some(process_group) = take_run(app, run_id) else {
+        return false;
+    };
+    kill_process_group(run_id, process_group);
+    true
+}
+
+// The memory cap is applied by a shell that then execs the interpreter, so the limit
+// covers the snippet and anything it starts
+fn limited_program(program: Vec<String>) -> Vec<String> {
+    if !MEMORY_LIMIT_ENFORCED {
+        return program;
+    }
+    let mut args = vec![
+        "sh".to_string(),
+        "-c".to_string(),
+        format!("ulimit -d {} && exec \"$@\"", MEMORY_LIMIT_MB * 1024),
+        "sh".to_string(),
+    ];
+    args.extend(program);
+    args
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .filter(|home| home.is_absolute() && home.parent().is_some())
+}
+
+// Linux: bubblewrap mounts the filesystem read-only with only the run directory writable,
+// hides the home directory and /tmp, and unshares every namespace, network included. In
+// its own PID namespace nothing the snippet starts outlives the sandbox.
+#[cfg(target_os = "linux")]
+fn sandboxed_command(run_dir: &Path, program: Vec<String>) -> Result<Vec<String>, String> {
+    let mut args: Vec<String> = [
+        "bwrap",
+        "--ro-bind",
+        "/",
+        "/",
+        "--dev",
+        "/dev",
+        "--proc",
+        "/proc",
+        "--tmpfs",
+        "/tmp",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    if let Some(home) = home_dir() {
+        args.extend(["--tmpfs".to_string(), home.display().to_string()]);
+        for dir in TOOLCHAIN_DIRS {
+            let path = home.join(dir).display().to_string();
+            args.extend(["--ro-bind-try".to_string(), path.clone(), path]);
+        }
+    }
+    let run_dir = run_dir.display().to_string();
+    args.extend(["--bind".to_string(), run_dir.clone(), run_dir]);
+    args.extend(
+        ["--unshare-all", "--die-with-parent", "--new-session", "--"]
+            .iter()
+            .map(|s| s.to_string()),
+    );
+    args.extend(limited_program(program));
+    Ok(args)
+}
+
+// Quotes a path for a Seatbelt profile
+#[cfg(target_os = "macos")]
+fn sandbox_string(path: &Path) -> String {
+    let path = path.display().to_string();
+    format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// macOS: a Seatbelt profile denies network access, writes outside the run directory and
+// reads of the home directory and external volumes
+#[cfg(target_os = "macos")]
+fn sandboxed_command(run_dir: &Path, program: Vec<String>) -> Result<Vec<String>, String> {
+    let mut profile = String::from(
+        "(version 1)
+        (allow default)
+        (deny network*)
+        (deny file-write*)
+        (allow file-write* (subpath (param \"RUN_DIR\")) (literal \"/dev/null\"))
+        (deny file-read* (subpath \"/Volumes\"))",
+    );
+    // Later rules take precedence, so the toolchain exceptions follow the home rule
+    if let Some(home) = home_dir() {
+        profile.push_str(&format!(
+            "\n(deny file-read* (subpath {}))",
+            sandbox_string(&home)
+        ));
+        for dir in TOOLCHAIN_DIRS {
+            profile.push_str(&format!(
+                "\n(allow file-read* (subpath {}))",
+                sandbox_string(&home.join(dir))
+            ));
+        }
+    }
+    profile.push_str("\n(allow file-read* (subpath (param \"RUN_DIR\")))");
+
+    let mut args = vec![
+        "sandbox-exec".to_string(),
+        "-D".to_string(),
+        format!("RUN_DIR={}", run_dir.display()),
+        "-p".to_string(),
+        profile,
+    ];
+    args.extend(limited_program(program));
+    Ok(args)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn sandboxed_command(_run_dir: &Path, _program: Vec<String>) -> Result<Vec<String>, String> {
+    Err("Sandboxed code execution is not supported on this platform".to_string())
+}
+
+fn create_run_dir(run_id: &str) -> Result<PathBuf, String> {
+    let dir = std::env::temp_dir().join(format!("pluely-run-{}", run_id));
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create run directory: {}", e))?;
+    // Sandbox profiles match on the real path (/var is a symlink on macOS)
+    dir.canonicalize()
+        .map_err(|e| format!("Failed to resolve run directory: {}", e))
+}
+
+// Forwards a pipe of the child as (stream, bytes) chunks until it closes
+fn forward_output(
+    mut reader: impl AsyncRead + Unpin + Send + 'static,
+    stream: &'static str,
+    output: mpsc::UnboundedSender<(&'static str, Vec<u8>)>,
+) {
+    tauri::async_runtime::spawn(async move {
+        let mut buffer = vec![0; 8192];
+        loop {
+            match reader.read(&mut buffer).await {
+                Ok(0) | Err(_) => break,
+                Ok(read) => {
+                    if output.send((stream, buffer[..read].to_vec())).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+async fn watch_run(
+    app: AppHandle,
+    run_id: String,
+    run_dir: PathBuf,
+    mut child: tokio::process::Child,
+    timeout: Duration,
+) {
+    let started = Instant::now();
+    let deadline = tokio::time::sleep(timeout);
+    tokio::pin!(deadline);
+    let mut status: Option<CodeRunStatus> = None;
+    let mut output_bytes = 0;
+
+    let (output_tx, mut output) = mpsc::unbounded_channel();
+    if let Some(stdout) = child.stdout.take() {
+        forward_output(stdout, "stdout", output_tx.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_output(stderr, "stderr", output_tx);
+    }
+
+    // The pipes close once the snippet and everything that inherited them are gone
+    loop {
+        tokio::select! {
+            chunk = output.recv() => {
+                let Some((stream, bytes)) = chunk else {
+                    break;
+                };
+                if status.is_some() {
+                    continue;
+                }
+
+                output_bytes += bytes.len();
+                if output_bytes > MAX_OUTPUT_BYTES {
+                    status = Some(CodeRunStatus::OutputLimit);
+                    kill_run(&app, &run_id);
+                    continue;
+                }
+                let _ = app.emit(
+                    "code-run-output",
+                    CodeRunOutput {
+                        run_id: &run_id,
+                        stream,
+                        data: String::from_utf8_lossy(&bytes).to_string(),
+                    },
+                );
+            }
+            _ = &mut deadline, if status.is_none() => {
+                status = Some(CodeRunStatus::TimedOut);
+                kill_run(&app, &run_id);
+            }
+        }
+    }
+    let exit_code = match child.wait().await {
+        Ok(exit) => exit.code(),
+        Err(e) => {
+            let _ = app.emit(
+                "code-run-output",
+                CodeRunOutput {
+                    run_id: &run_id,
+                    stream: "stderr",
+                    data: e.to_string(),
+                },
+            );
+            None
+        }
+    };
+
+    // A run missing from the table without us killing it was cancelled by the user
+    let status = status.unwrap_or_else(|| match take_run(&app, &run_id) {
+        Some(process_group) => {
+            // Background processes that let go of the output pipes don't outlive the run
+            kill_process_group(&run_id, process_group);
+            CodeRunStatus::Exited
+        }
+        None => CodeRunStatus::Cancelled,
+    });
+    let _ = app.emit(
+        "code-run-finished",
+        CodeRunFinished {
+            run_id: &run_id,
+            status,
+            exit_code,
+            duration_ms: started.elapsed().as_millis() as u64,
+            memory_limit_mb: MEMORY_LIMIT_ENFORCED.then_some(MEMORY_LIMIT_MB),
+        },
+    );
+
+    if let Err(e) = fs::remove_dir_all(&run_dir) {
+        tracing::warn!(
+            "Failed to remove run directory {}: {}",
+            run_dir.display(),
+            e
+        );
+    }
+}
+
+// Starts the snippet and returns its run id right away; output arrives as
+// `code-run-output` events followed by one `code-run-finished` event
+#[tauri::command]
+pub async fn run_code_snippet(
+    app: AppHandle,
+    language: SnippetLanguage,
+    code: String,
+    timeout_secs: Option<u64>,
+) -> Result<String, String> {
+    if code.trim().is_empty() {
+        return Err("Snippet is empty".to_string());
+    }
+    if code.len() > MAX_CODE_BYTES {
+        return Err(format!(
+            "Snippet must be at most {} KB",
+            MAX_CODE_BYTES / 1024
+        ));
+    }
+    let timeout = timeout_secs
+        .unwrap_or(DEFAULT_TIMEOUT_SECS)
+        .clamp(1, MAX_TIMEOUT_SECS);
+
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let run_dir = create_run_dir(&run_id)?;
+    let (file_name, program) = language.program();
+
+    let spawned = fs::write(run_dir.join(file_name), &code)
+        .map_err(|e| format!("Failed to write snippet: {}", e))
+        .and_then(|_| sandboxed_command(&run_dir, program))
+        .and_then(|args| {
+            let command = app
+                .shell()
+                .command(&args[0])
+                .args(&args[1..])
+                .current_dir(&run_dir)
+                .env_clear()
+                .env("PATH", std::env::var("PATH").unwrap_or_default())
+                .env("HOME", &run_dir)
+                .env("TMPDIR", &run_dir)
+                .env("LANG", "C.UTF-8");
+            // Spawned here rather than with the plugin's `spawn`, which can't give the child
+            // its own process group; the snippet's children are killed with that group
+            let mut command = std::process::Command::from(command);
+            command
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+            #[cfg(unix)]
+            {
+                use std::os::unix::process::CommandExt;
+                command.process_group(0);
+            }
+            tokio::process::Command::from(command)
+                .spawn()
+                .map_err(|e| format!("Failed to start snippet with {}: {}", args[0], e))
+        });
+    let child = match spawned {
+        Ok(spawned) => spawned,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&run_dir);
+            return Err(e);
+        }
+    };
+
+    if let (Some(pid), Ok(mut runs)) = (child.id(), app.state::<CodeRunState>().runs.lock()) {
+        runs.insert(run_id.clone(), pid);
+    }
+    tauri::async_runtime::spawn(watch_run(
+        app.clone(),
+        run_id.clone(),
+        run_dir,
+        child,
+        Duration::from_secs(timeout),
+    ));
+
+    Ok(run_id)
+}
+
+#[tauri::command]
+pub fn cancel_code_run(app: AppHandle, run_id: String) -> Result<(), String> {
+    if kill_run(&app, &run_id) {
+        Ok(())
+    } else {
+        Err(format!("No running snippet with id {}", run_id))
+    }
+}
@@ -1,4 +1,18 @@
-use tauri_plugin_sql::{Migration, MigrationKind};
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_sql::{DbInstances, DbPool, Migration, MigrationKind};
+
+const DB_URL: &str = "sqlite:pluely.db";
+
+/// Returns the pool opened by the SQL plugin, for tables the backend reads itself
+pub async fn get_pool(app: &AppHandle) -> Result<Pool<Sqlite>, String> {
+    let instances = app.state::<DbInstances>();
+    let instances = instances.0.read().await;
+    match instances.get(DB_URL) {
+        Some(DbPool::Sqlite(pool)) => Ok(pool.clone()),
+        None => Err("Database is not loaded".to_string()),
+    }
+}
 
 /// Returns all database migrations
 pub fn migrations() -> Vec<Migration> {
@@ -17,5 +31,26 @@ pub fn migrations() -> Vec<Migration> {
             sql: include_str!("migrations/chat-history.sql"),
             kind: MigrationKind::Up,
         },
+        // Migration 3: Create outbox table for requests queued while offline
+        Migration {
+            version: 3,
+            description: "create_outbox_table",
+            sql: include_str!("migrations/outbox.sql"),
+            kind: MigrationKind::Up,
+        },
+        // Migration 4: Create vocabulary table for transcription hints
+        Migration {
+            version: 4,
+            description: "create_vocabulary_table",
+            sql: include_str!("migrations/vocabulary.sql"),
+            kind: MigrationKind::Up,
+        },
+        // Migration 5: Create transcription cache table
+        Migration {
+            version: 5,
+            description: "create_transcription_cache_table",
+            sql: include_str!("migrations/transcription-cache.sql"),
+            kind: MigrationKind::Up,
+        },
     ]
 }
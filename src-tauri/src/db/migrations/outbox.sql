@@ -0,0 +1,17 @@
+-- Create outbox table for requests made while offline
+CREATE TABLE IF NOT EXISTS outbox (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL CHECK(kind IN ('chat', 'transcription')),
+    conversation_id TEXT,
+    preview TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK(status IN ('pending', 'sending', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+-- Pending items are replayed oldest first
+CREATE INDEX IF NOT EXISTS idx_outbox_status_created_at ON outbox(status, created_at ASC);
+CREATE INDEX IF NOT EXISTS idx_outbox_conversation_id ON outbox(conversation_id);
@@ -0,0 +1,15 @@
+-- Create transcription cache keyed by audio hash
+CREATE TABLE IF NOT EXISTS transcription_cache (
+    audio_hash TEXT NOT NULL,
+    model TEXT NOT NULL,
+    settings_hash TEXT NOT NULL,
+    transcription TEXT NOT NULL,
+    language TEXT,
+    segments TEXT NOT NULL DEFAULT '[]',
+    created_at INTEGER NOT NULL,
+    last_used_at INTEGER NOT NULL,
+    PRIMARY KEY (audio_hash, model, settings_hash)
+);
+
+-- Least recently used entries are evicted first
+CREATE INDEX IF NOT EXISTS idx_transcription_cache_last_used_at ON transcription_cache(last_used_at ASC);
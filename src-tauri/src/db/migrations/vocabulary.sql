@@ -0,0 +1,6 @@
+-- Create vocabulary table for transcription hints (product names, people, acronyms)
+CREATE TABLE IF NOT EXISTS vocabulary (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    term TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    created_at TEXT DEFAULT (datetime('now')) NOT NULL
+);
@@ -0,0 +1,529 @@
+// Local override rules for provider error messages, plus a diagnostics log of raw errors
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+const MAX_DIAGNOSTICS: usize = 50;
+const MAX_RAW_BODY_CHARS: usize = 4000;
+
+// What the user should try next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorAction {
+    Retry,
+    Wait,
+    SwitchModel,
+    CheckKey,
+    CheckConnection,
+    ContactSupport,
+}
+
+// Substring or regex match against one field of the error
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TextMatcher {
+    #[serde(default)]
+    pub contains: Option<String>,
+    #[serde(default)]
+    pub regex: Option<String>,
+    #[serde(default)]
+    pub case_insensitive: bool,
+}
+
+impl TextMatcher {
+    fn matches(&self, text: &str) -> bool {
+        if let Some(needle) = self.contains.as_deref().filter(|n| !n.is_empty()) {
+            let found = if self.case_insensitive {
+                text.to_lowercase().contains(&needle.to_lowercase())
+            } else {
+                text.contains(needle)
+            };
+            if !found {
+                return false;
+            }
+        }
+
+        if let Some(pattern) = self.regex.as_deref().filter(|p| !p.is_empty()) {
+            match RegexBuilder::new(pattern)
+                .case_insensitive(self.case_insensitive)
+                .build()
+            {
+                Ok(re) => {
+                    if !re.is_match(text) {
+                        return false;
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Invalid error rule regex '{}': {}", pattern, e);
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+// A rule from the local `error_rules.json` file. Every matcher that is set must match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalErrorRule {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub status: Option<TextMatcher>,
+    #[serde(default)]
+    pub body: Option<TextMatcher>,
+    #[serde(default)]
+    pub error_type: Option<TextMatcher>,
+    pub message: String,
+    #[serde(default)]
+    pub action: Option<ErrorAction>,
+}
+
+impl LocalErrorRule {
+    fn matches(&self, error: &ProviderError) -> bool {
+        if self.status.is_none() && self.body.is_none() && self.error_type.is_none() {
+            return false;
+        }
+
+        let status = error.status.map(|s| s.to_string()).unwrap_or_default();
+        let error_type = error.error_type.as_deref().unwrap_or_default();
+
+        self.status.as_ref().is_none_or(|m| m.matches(&status))
+            && self.body.as_ref().is_none_or(|m| m.matches(&error.body))
+            && self
+                .error_type
+                .as_ref()
+                .is_none_or(|m| m.matches(error_type))
+    }
+}
+
+// Raw error as received from a provider call
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderError {
+    pub endpoint: String,
+    pub status: Option<u16>,
+    pub body: String,
+    pub error_type: Option<String>,
+}
+
+impl ProviderError {
+    // Error raised before a response arrived (DNS, TLS, timeout, dropped stream...)
+    pub fn from_transport(endpoint: &str, error: &reqwest::Error) -> Self {
+        let error_type = if error.is_timeout() {
+            "timeout"
+        } else if error.is_connect() {
+            "connect"
+        } else if error.is_decode() {
+            "decode"
+        } else if error.is_body() {
+            "body"
+        } else {
+            "request"
+        };
+
+        Self {
+            endpoint: endpoint.to_string(),
+            status: error.status().map(|s| s.as_u16()),
+            body: error.to_string(),
+            error_type: Some(error_type.to_string()),
+        }
+    }
+
+    // Non-success HTTP response; the error type is read from common JSON error shapes
+    pub fn from_response(endpoint: &str, status: u16, body: &str) -> Self {
+        let error_type = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|json| {
+                let error = json.get("error");
+                error
+                    .and_then(|e| e.get("type").or_else(|| e.get("code")))
+                    .or_else(|| json.get("type"))
+                    .or_else(|| json.get("code"))
+                    .and_then(|t| match t {
+                        serde_json::Value::String(s) => Some(s.clone()),
+                        serde_json::Value::Number(n) => Some(n.to_string()),
+                        _ => None,
+                    })
+            });
+
+        Self {
+            endpoint: endpoint.to_string(),
+            status: Some(status),
+            body: body.to_string(),
+            error_type,
+        }
+    }
+
+    // Failure without an HTTP error, e.g. an empty response
+    pub fn local(endpoint: &str, error_type: &str, message: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            status: None,
+            body: message.to_string(),
+            error_type: Some(error_type.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            Some(status) => write!(f, "{} returned {}: {}", self.endpoint, status, self.body),
+            None => f.write_str(&self.body),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleSource {
+    Local,
+    Server,
+    Default,
+}
+
+// User-facing message chosen for an error
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedError {
+    pub message: String,
+    pub action: ErrorAction,
+    pub source: RuleSource,
+    pub rule_id: Option<String>,
+}
+
+impl ResolvedError {
+    // Message picked by the server-delivered rules (or the generic fallback)
+    pub fn from_server(message: String, error: &ProviderError, has_server_rules: bool) -> Self {
+        Self {
+            message,
+            action: suggested_action(error),
+            source: if has_server_rules {
+                RuleSource::Server
+            } else {
+                RuleSource::Default
+            },
+            rule_id: None,
+        }
+    }
+}
+
+// Best guess when no rule specifies an action
+fn suggested_action(error: &ProviderError) -> ErrorAction {
+    match error.status {
+        Some(401) | Some(403) => ErrorAction::CheckKey,
+        Some(429) => ErrorAction::Wait,
+        Some(status) if status >= 500 => ErrorAction::Retry,
+        Some(_) => ErrorAction::SwitchModel,
+        None => match error.error_type.as_deref() {
+            Some("connect") | Some("timeout") => ErrorAction::CheckConnection,
+            _ => ErrorAction::Retry,
+        },
+    }
+}
+
+// Diagnostics entry kept for support, including the raw error that was matched
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorDiagnostic {
+    pub id: String,
+    pub timestamp: i64,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub error: ProviderError,
+    pub resolved: ResolvedError,
+}
+
+#[derive(Default)]
+pub struct ErrorDiagnosticsState {
+    entries: Mutex<VecDeque<ErrorDiagnostic>>,
+}
+
+fn get_error_rules_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+    Ok(app_data_dir.join("error_rules.json"))
+}
+
+fn load_local_error_rules(app: &AppHandle) -> Vec<LocalErrorRule> {
+    let Ok(path) = get_error_rules_path(app) else {
+        return Vec::new();
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        tracing::warn!("Failed to parse {}: {}", path.display(), e);
+        Vec::new()
+    })
+}
+
+// Returns the first local rule matching the error, if any
+pub fn match_local_error_rule(app: &AppHandle, error: &ProviderError) -> Option<ResolvedError> {
+    first_matching_rule(load_local_error_rules(app), error)
+}
+
+fn first_matching_rule(rules: Vec<LocalErrorRule>, error: &ProviderError) -> Option<ResolvedError> {
+    rules
+        .into_iter()
+        .find(|rule| rule.matches(error))
+        .map(|rule| ResolvedError {
+            message: rule.message,
+            action: rule.action.unwrap_or_else(|| suggested_action(error)),
+            source: RuleSource::Local,
+            rule_id: rule.id,
+        })
+}
+
+// Stores the raw error and the chosen message so the diagnostics view can show both
+pub fn record_error_diagnostic(
+    app: &AppHandle,
+    mut error: ProviderError,
+    resolved: ResolvedError,
+    provider: Option<String>,
+    model: Option<String>,
+) -> ErrorDiagnostic {
+    if error.body.chars().count() > MAX_RAW_BODY_CHARS {
+        error.body = error.body.chars().take(MAX_RAW_BODY_CHARS).collect();
+        error.body.push('…');
+    }
+
+    let diagnostic = ErrorDiagnostic {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        provider,
+        model,
+        error,
+        resolved,
+    };
+
+    let state = app.state::<ErrorDiagnosticsState>();
+    let mut entries = match state.entries.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    entries.push_back(diagnostic.clone());
+    while entries.len() > MAX_DIAGNOSTICS {
+        entries.pop_front();
+    }
+
+    diagnostic
+}
+
+#[tauri::command]
+pub fn get_error_diagnostics(app: AppHandle) -> Result<Vec<ErrorDiagnostic>, String> {
+    let state = app.state::<ErrorDiagnosticsState>();
+    let entries = state
+        .entries
+        .lock()
+        .map_err(|e| format!("Failed to read error diagnostics: {}", e))?;
+    Ok(entries.iter().rev().cloned().collect())
+}
+
+#[tauri::command]
+pub fn clear_error_diagnostics(app: AppHandle) -> Result<(), String> {
+    let state = app.state::<ErrorDiagnosticsState>();
+    state
+        .entries
+        .lock()
+        .map_err(|e| format!("Failed to clear error diagnostics: {}", e))?
+        .clear();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_error_rules(app: AppHandle) -> Result<Vec<LocalErrorRule>, String> {
+    Ok(load_local_error_rules(&app))
+}
+
+#[tauri::command]
+pub fn save_error_rules(app: AppHandle, rules: Vec<LocalErrorRule>) -> Result<(), String> {
+    for rule in &rules {
+        for matcher in [&rule.status, &rule.body, &rule.error_type]
+            .into_iter()
+            .flatten()
+        {
+            if let Some(pattern) = matcher.regex.as_deref().filter(|p| !p.is_empty()) {
+                RegexBuilder::new(pattern)
+                    .build()
+                    .map_err(|e| format!("Invalid regex '{}': {}", pattern, e))?;
+            }
+        }
+    }
+
+    let path = get_error_rules_path(&app)?;
+    let content = serde_json::to_string_pretty(&rules)
+        .map_err(|e| format!("Failed to serialize error rules: {}", e))?;
+
+    fs::write(&path, content).map_err(|e| format!("Failed to write error rules: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contains(needle: &str) -> Option<TextMatcher> {
+        Some(TextMatcher {
+            contains: Some(needle.to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn regex(pattern: &str) -> Option<TextMatcher> {
+        Some(TextMatcher {
+            regex: Some(pattern.to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn rule(id: &str) -> LocalErrorRule {
+        LocalErrorRule {
+            id: Some(id.to_string()),
+            status: None,
+            body: None,
+            error_type: None,
+            message: format!("{} message", id),
+            action: None,
+        }
+    }
+
+    fn quota_error() -> ProviderError {
+        ProviderError::from_response(
+            "/api/transcribe",
+            429,
+            r#"{"error": {"type": "insufficient_quota", "message": "You exceeded your current quota"}}"#,
+        )
+    }
+
+    #[test]
+    fn reads_error_type_from_common_shapes() {
+        assert_eq!(
+            quota_error().error_type.as_deref(),
+            Some("insufficient_quota")
+        );
+        let coded = ProviderError::from_response("/api/chat", 400, r#"{"error": {"code": 1001}}"#);
+        assert_eq!(coded.error_type.as_deref(), Some("1001"));
+        let flat = ProviderError::from_response("/api/chat", 401, r#"{"type": "auth_error"}"#);
+        assert_eq!(flat.error_type.as_deref(), Some("auth_error"));
+        let plain = ProviderError::from_response("/api/chat", 502, "Bad Gateway");
+        assert_eq!(plain.error_type, None);
+    }
+
+    #[test]
+    fn matchers_use_substrings_and_regexes() {
+        let error = quota_error();
+
+        let by_status = LocalErrorRule {
+            status: regex("^4(29|03)$"),
+            ..rule("status")
+        };
+        assert!(by_status.matches(&error));
+
+        let by_body = LocalErrorRule {
+            body: contains("current quota"),
+            ..rule("body")
+        };
+        assert!(by_body.matches(&error));
+
+        let by_type = LocalErrorRule {
+            error_type: Some(TextMatcher {
+                contains: Some("QUOTA".to_string()),
+                case_insensitive: true,
+                ..Default::default()
+            }),
+            ..rule("type")
+        };
+        assert!(by_type.matches(&error));
+
+        let wrong_case = LocalErrorRule {
+            error_type: contains("QUOTA"),
+            ..rule("case")
+        };
+        assert!(!wrong_case.matches(&error));
+    }
+
+    #[test]
+    fn every_matcher_must_match() {
+        let error = quota_error();
+        let both = LocalErrorRule {
+            status: contains("429"),
+            body: contains("invalid api key"),
+            ..rule("both")
+        };
+        assert!(!both.matches(&error));
+
+        // A rule without matchers would swallow every error
+        assert!(!rule("empty").matches(&error));
+
+        let invalid = LocalErrorRule {
+            body: regex("(unclosed"),
+            ..rule("invalid")
+        };
+        assert!(!invalid.matches(&error));
+    }
+
+    #[test]
+    fn first_matching_rule_wins_and_falls_back_to_suggested_action() {
+        let rules = vec![
+            LocalErrorRule {
+                status: contains("401"),
+                action: Some(ErrorAction::ContactSupport),
+                ..rule("key")
+            },
+            LocalErrorRule {
+                error_type: contains("insufficient_quota"),
+                ..rule("quota")
+            },
+            LocalErrorRule {
+                status: contains("429"),
+                ..rule("rate")
+            },
+        ];
+
+        let resolved = first_matching_rule(rules.clone(), &quota_error()).unwrap();
+        assert_eq!(resolved.rule_id.as_deref(), Some("quota"));
+        assert_eq!(resolved.message, "quota message");
+        assert_eq!(resolved.action, ErrorAction::Wait);
+
+        let bad_key = ProviderError::from_response("/api/chat", 401, "Unauthorized");
+        let resolved = first_matching_rule(rules.clone(), &bad_key).unwrap();
+        assert_eq!(resolved.action, ErrorAction::ContactSupport);
+
+        let server = ProviderError::from_response("/api/chat", 500, "oops");
+        assert!(first_matching_rule(rules, &server).is_none());
+    }
+
+    #[test]
+    fn suggests_actions_from_status_and_transport_errors() {
+        let status = |code| ProviderError::from_response("/api/chat", code, "");
+        assert_eq!(suggested_action(&status(401)), ErrorAction::CheckKey);
+        assert_eq!(suggested_action(&status(403)), ErrorAction::CheckKey);
+        assert_eq!(suggested_action(&status(429)), ErrorAction::Wait);
+        assert_eq!(suggested_action(&status(503)), ErrorAction::Retry);
+        assert_eq!(suggested_action(&status(400)), ErrorAction::SwitchModel);
+
+        let local = |error_type| ProviderError::local("/api/chat", error_type, "failed");
+        assert_eq!(
+            suggested_action(&local("connect")),
+            ErrorAction::CheckConnection
+        );
+        assert_eq!(
+            suggested_action(&local("timeout")),
+            ErrorAction::CheckConnection
+        );
+        assert_eq!(
+            suggested_action(&local("empty_response")),
+            ErrorAction::Retry
+        );
+    }
+}
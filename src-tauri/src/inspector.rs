@@ -0,0 +1,476 @@
+// Request/response inspector: bounded log of outbound provider calls for debugging
+use reqwest::Request;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+const MAX_CAPACITY: usize = 500;
+const MAX_STRING_CHARS: usize = 4000;
+const MAX_DATA_URL_PREVIEW: usize = 64;
+const MAX_EVENTS_PER_ENTRY: usize = 2000;
+const REDACTED: &str = "<redacted>";
+// Calls finishing within this window share one write of the persisted log
+const PERSIST_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallKind {
+    Chat,
+    Transcription,
+    Models,
+    Config,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectorConfig {
+    pub enabled: bool,
+    pub capacity: usize,
+    pub persist: bool,
+}
+
+impl Default for InspectorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            capacity: 50,
+            persist: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InspectorEntry {
+    pub id: String,
+    pub kind: CallKind,
+    pub method: String,
+    pub url: String,
+    pub request_headers: Vec<(String, String)>,
+    pub request_body: Option<Value>,
+    pub status: Option<u16>,
+    pub started_at: i64,
+    pub duration_ms: Option<u64>,
+    pub time_to_first_token_ms: Option<u64>,
+    pub events: Vec<String>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct InspectorState {
+    entries: Mutex<VecDeque<InspectorEntry>>,
+    config: Mutex<InspectorConfig>,
+    // Set while a write of the persisted log is pending
+    persist_scheduled: AtomicBool,
+}
+
+// One in-flight call. The entry is committed to the log when this is dropped,
+// so early returns and errors are recorded without extra bookkeeping.
+pub struct InspectorCall {
+    app: Option<AppHandle>,
+    entry: InspectorEntry,
+    started: Instant,
+}
+
+impl InspectorCall {
+    pub fn begin(
+        app: &AppHandle,
+        kind: CallKind,
+        request: Option<&Request>,
+        body: Option<&Value>,
+    ) -> Self {
+        let enabled = app
+            .state::<InspectorState>()
+            .config
+            .lock()
+            .map(|config| config.enabled)
+            .unwrap_or(false);
+
+        let (method, url, request_headers) = match request {
+            Some(request) => (
+                request.method().to_string(),
+                redact_url(request.url()),
+                request
+                    .headers()
+                    .iter()
+                    .map(|(name, value)| {
+                        let value = value.to_str().unwrap_or("<binary>");
+                        (name.to_string(), redact_header(name.as_str(), value))
+                    })
+                    .collect(),
+            ),
+            None => (String::new(), String::new(), Vec::new()),
+        };
+
+        Self {
+            app: enabled.then(|| app.clone()),
+            entry: InspectorEntry {
+                id: uuid::Uuid::new_v4().to_string(),
+                kind,
+                method,
+                url,
+                request_headers,
+                request_body: body.map(sanitize_json),
+                status: None,
+                started_at: chrono::Utc::now().timestamp_millis(),
+                duration_ms: None,
+                time_to_first_token_ms: None,
+                events: Vec::new(),
+                response_body: None,
+                error: None,
+            },
+            started: Instant::now(),
+        }
+    }
+
+    pub fn set_status(&mut self, status: u16) {
+        self.entry.status = Some(status);
+    }
+
+    pub fn mark_first_token(&mut self) {
+        if self.entry.time_to_first_token_ms.is_none() {
+            self.entry.time_to_first_token_ms = Some(self.started.elapsed().as_millis() as u64);
+        }
+    }
+
+    pub fn push_event(&mut self, raw: &str) {
+        if self.app.is_some() && self.entry.events.len() < MAX_EVENTS_PER_ENTRY {
+            self.entry
+                .events
+                .push(truncate_chars(raw, MAX_STRING_CHARS));
+        }
+    }
+
+    pub fn set_response_body(&mut self, body: &str) {
+        if self.app.is_none() {
+            return;
+        }
+        let body = match serde_json::from_str::<Value>(body) {
+            Ok(json) => sanitize_json(&json).to_string(),
+            Err(_) => body.to_string(),
+        };
+        self.entry.response_body = Some(truncate_chars(&body, MAX_STRING_CHARS));
+    }
+
+    pub fn set_error(&mut self, error: impl Into<String>) {
+        self.entry.error = Some(error.into());
+    }
+}
+
+impl Drop for InspectorCall {
+    fn drop(&mut self) {
+        let Some(app) = self.app.take() else {
+            return;
+        };
+
+        let events = std::mem::take(&mut self.entry.events);
+        let mut entry = self.entry.clone();
+        entry.events = events;
+        entry.duration_ms = Some(self.started.elapsed().as_millis() as u64);
+
+        let state = app.state::<InspectorState>();
+        let config = match state.config.lock() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+
+        {
+            let mut entries = match state.entries.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            entries.push_back(entry);
+            while entries.len() > config.capacity {
+                entries.pop_front();
+            }
+        }
+
+        if config.persist && !state.persist_scheduled.swap(true, Ordering::AcqRel) {
+            schedule_persist(app);
+        }
+    }
+}
+
+// Writes the log shortly after a call finishes, off the caller's thread
+fn schedule_persist(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(PERSIST_DELAY).await;
+        let written = tauri::async_runtime::spawn_blocking(move || {
+            let state = app.state::<InspectorState>();
+            state.persist_scheduled.store(false, Ordering::Release);
+            let persist = state.config.lock().map(|c| c.persist).unwrap_or(false);
+            if !persist {
+                return Ok(());
+            }
+            let entries: Vec<InspectorEntry> = match state.entries.lock() {
+                Ok(guard) => guard.iter().cloned().collect(),
+                Err(poisoned) => poisoned.into_inner().iter().cloned().collect(),
+            };
+            write_persisted_entries(&app, &entries)
+        })
+        .await;
+
+        match written {
+            Ok(Err(e)) => tracing::warn!("Failed to persist inspector log: {}", e),
+            Err(e) => tracing::warn!("Failed to persist inspector log: {}", e),
+            Ok(Ok(())) => {}
+        }
+    });
+}
+
+fn is_sensitive_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name == "authorization"
+        || name == "proxy-authorization"
+        || name == "cookie"
+        || name == "instance"
+        || name == "machine_id"
+        || ["key", "token", "secret", "password", "license", "signature"]
+            .iter()
+            .any(|needle| name.contains(needle))
+}
+
+fn redact_header(name: &str, value: &str) -> String {
+    if is_sensitive_name(name) {
+        REDACTED.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn redact_url(url: &reqwest::Url) -> String {
+    if url.query().is_none() {
+        return url.to_string();
+    }
+
+    let mut redacted = url.clone();
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| {
+            let value = if is_sensitive_name(&key) {
+                REDACTED.to_string()
+            } else {
+                value.into_owned()
+            };
+            (key.into_owned(), value)
+        })
+        .collect();
+    redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    redacted.to_string()
+}
+
+// Truncates images/long strings and hides secrets before a body is stored
+fn sanitize_json(value: &Value) -> Value {
+    match value {
+        Value::String(text) => {
+            if text.starts_with("data:") && text.len() > MAX_DATA_URL_PREVIEW {
+                let preview: String = text.chars().take(MAX_DATA_URL_PREVIEW).collect();
+                Value::String(format!(
+                    "{}… ({} bytes truncated)",
+                    preview,
+                    text.len() - preview.len()
+                ))
+            } else {
+                Value::String(truncate_chars(text, MAX_STRING_CHARS))
+            }
+        }
+        Value::Array(items) => Value::Array(items.iter().map(sanitize_json).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, field)| {
+                    let field = if is_sensitive_name(key) && field.is_string() {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        sanitize_json(field)
+                    };
+                    (key.clone(), field)
+                })
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((index, _)) => format!("{}… (truncated)", &text[..index]),
+        None => text.to_string(),
+    }
+}
+
+fn get_inspector_log_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+    Ok(app_data_dir.join("inspector_log.json"))
+}
+
+fn write_persisted_entries(app: &AppHandle, entries: &[InspectorEntry]) -> Result<(), String> {
+    let path = get_inspector_log_path(app)?;
+    let content = serde_json::to_string(entries)
+        .map_err(|e| format!("Failed to serialize inspector log: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write inspector log: {}", e))
+}
+
+// Restores the persisted log on startup; the log file only exists when persistence is on
+pub fn load_persisted_entries(app: &AppHandle) {
+    let Ok(path) = get_inspector_log_path(app) else {
+        return;
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return;
+    };
+    let Ok(persisted) = serde_json::from_str::<Vec<InspectorEntry>>(&content) else {
+        tracing::warn!("Ignoring unreadable inspector log at {}", path.display());
+        return;
+    };
+
+    let state = app.state::<InspectorState>();
+    let capacity = match state.config.lock() {
+        Ok(mut config) => {
+            config.persist = true;
+            config.capacity
+        }
+        Err(_) => return,
+    };
+    let Ok(mut entries) = state.entries.lock() else {
+        return;
+    };
+    entries.extend(persisted);
+    while entries.len() > capacity {
+        entries.pop_front();
+    }
+}
+
+#[tauri::command]
+pub fn get_inspector_entries(app: AppHandle) -> Result<Vec<InspectorEntry>, String> {
+    let state = app.state::<InspectorState>();
+    let entries = state
+        .entries
+        .lock()
+        .map_err(|e| format!("Failed to read inspector log: {}", e))?;
+    Ok(entries.iter().rev().cloned().collect())
+}
+
+// Exports the log as pretty JSON. With `file_name`, writes it to the `inspector_exports`
+// folder in the app data directory and returns the full path; otherwise returns the JSON.
+#[tauri::command]
+pub fn export_inspector_entries(
+    app: AppHandle,
+    file_name: Option<String>,
+) -> Result<String, String> {
+    let entries: Vec<InspectorEntry> = {
+        let state = app.state::<InspectorState>();
+        let entries = state
+            .entries
+            .lock()
+            .map_err(|e| format!("Failed to read inspector log: {}", e))?;
+        entries.iter().cloned().collect()
+    };
+
+    let content = serde_json::to_string_pretty(&entries)
+        .map_err(|e| format!("Failed to serialize inspector log: {}", e))?;
+
+    match file_name {
+        Some(file_name) => {
+            let path = get_export_path(&app, &file_name)?;
+            fs::write(&path, &content)
+                .map_err(|e| format!("Failed to export inspector log: {}", e))?;
+            Ok(path.to_string_lossy().to_string())
+        }
+        None => Ok(content),
+    }
+}
+
+// Only plain `.json` file names are accepted, so exports can't leave the export folder
+fn get_export_path(app: &AppHandle, file_name: &str) -> Result<PathBuf, String> {
+    let valid = !file_name.starts_with('.')
+        && file_name.ends_with(".json")
+        && file_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(format!("Invalid export file name: {}", file_name));
+    }
+
+    let export_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?
+        .join("inspector_exports");
+    fs::create_dir_all(&export_dir)
+        .map_err(|e| format!("Failed to create export directory: {}", e))?;
+
+    Ok(export_dir.join(file_name))
+}
+
+#[tauri::command]
+pub fn clear_inspector_entries(app: AppHandle) -> Result<(), String> {
+    let state = app.state::<InspectorState>();
+    state
+        .entries
+        .lock()
+        .map_err(|e| format!("Failed to clear inspector log: {}", e))?
+        .clear();
+
+    if let Ok(path) = get_inspector_log_path(&app) {
+        let _ = fs::remove_file(path);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_inspector_config(app: AppHandle) -> Result<InspectorConfig, String> {
+    let state = app.state::<InspectorState>();
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to read inspector config: {}", e))?
+        .clone();
+    Ok(config)
+}
+
+#[tauri::command]
+pub fn update_inspector_config(app: AppHandle, config: InspectorConfig) -> Result<(), String> {
+    if config.capacity == 0 || config.capacity > MAX_CAPACITY {
+        return Err(format!(
+            "Invalid capacity: must be between 1 and {}",
+            MAX_CAPACITY
+        ));
+    }
+
+    let state = app.state::<InspectorState>();
+    let entries: Vec<InspectorEntry> = {
+        let mut entries = state
+            .entries
+            .lock()
+            .map_err(|e| format!("Failed to update inspector log: {}", e))?;
+        while entries.len() > config.capacity {
+            entries.pop_front();
+        }
+        entries.iter().cloned().collect()
+    };
+
+    if config.persist {
+        write_persisted_entries(&app, &entries)?;
+    } else if let Ok(path) = get_inspector_log_path(&app) {
+        let _ = fs::remove_file(path);
+    }
+
+    *state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to update inspector config: {}", e))? = config;
+
+    Ok(())
+}
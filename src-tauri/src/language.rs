@@ -0,0 +1,159 @@
+// Transcription language settings: auto-detect, a fixed language, or a list of allowed languages
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+// ISO 639-1 codes and the names Whisper-style providers report in `verbose_json`
+const LANGUAGES: &[(&str, &str)] = &[
+    ("af", "afrikaans"),
+    ("ar", "arabic"),
+    ("hy", "armenian"),
+    ("az", "azerbaijani"),
+    ("be", "belarusian"),
+    ("bs", "bosnian"),
+    ("bg", "bulgarian"),
+    ("bn", "bengali"),
+    ("ca", "catalan"),
+    ("zh", "chinese"),
+    ("hr", "croatian"),
+    ("cs", "czech"),
+    ("da", "danish"),
+    ("nl", "dutch"),
+    ("en", "english"),
+    ("et", "estonian"),
+    ("fi", "finnish"),
+    ("fr", "french"),
+    ("gl", "galician"),
+    ("de", "german"),
+    ("el", "greek"),
+    ("gu", "gujarati"),
+    ("he", "hebrew"),
+    ("hi", "hindi"),
+    ("hu", "hungarian"),
+    ("is", "icelandic"),
+    ("id", "indonesian"),
+    ("it", "italian"),
+    ("ja", "japanese"),
+    ("kn", "kannada"),
+    ("kk", "kazakh"),
+    ("ko", "korean"),
+    ("lv", "latvian"),
+    ("lt", "lithuanian"),
+    ("mk", "macedonian"),
+    ("ms", "malay"),
+    ("ml", "malayalam"),
+    ("mr", "marathi"),
+    ("mi", "maori"),
+    ("ne", "nepali"),
+    ("no", "norwegian"),
+    ("fa", "persian"),
+    ("pl", "polish"),
+    ("pt", "portuguese"),
+    ("pa", "punjabi"),
+    ("ro", "romanian"),
+    ("ru", "russian"),
+    ("sr", "serbian"),
+    ("sk", "slovak"),
+    ("sl", "slovenian"),
+    ("es", "spanish"),
+    ("sw", "swahili"),
+    ("sv", "swedish"),
+    ("tl", "tagalog"),
+    ("ta", "tamil"),
+    ("te", "telugu"),
+    ("th", "thai"),
+    ("tr", "turkish"),
+    ("uk", "ukrainian"),
+    ("ur", "urdu"),
+    ("vi", "vietnamese"),
+    ("cy", "welsh"),
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum LanguageSetting {
+    // Let the provider detect the language
+    #[default]
+    Auto,
+    // Always transcribe as this language
+    Fixed {
+        language: String,
+    },
+    // Detect, but only accept one of these; misdetections are re-transcribed
+    Allowed {
+        languages: Vec<String>,
+    },
+}
+
+// Accepts ISO 639-1 codes, locale tags ("de-DE", "pt_BR") or English names ("German")
+// and returns the ISO 639-1 code
+pub fn normalize_language(value: &str) -> Option<String> {
+    let value = value.trim().to_lowercase();
+    let base = value.split(['-', '_']).next().unwrap_or_default();
+
+    LANGUAGES
+        .iter()
+        .find(|(code, name)| *code == base || *name == value)
+        .map(|(code, _)| code.to_string())
+}
+
+fn get_language_setting_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+    Ok(app_data_dir.join("transcription_language.json"))
+}
+
+pub fn load_language_setting(app: &AppHandle) -> LanguageSetting {
+    let Ok(path) = get_language_setting_path(app) else {
+        return LanguageSetting::default();
+    };
+
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn get_transcription_language(app: AppHandle) -> Result<LanguageSetting, String> {
+    Ok(load_language_setting(&app))
+}
+
+#[tauri::command]
+pub fn save_transcription_language(app: AppHandle, setting: LanguageSetting) -> Result<(), String> {
+    let unknown = |language: &String| format!("Unsupported language: {}", language);
+    let setting = match setting {
+        LanguageSetting::Auto => LanguageSetting::Auto,
+        LanguageSetting::Fixed { language } => LanguageSetting::Fixed {
+            language: normalize_language(&language).ok_or_else(|| unknown(&language))?,
+        },
+        LanguageSetting::Allowed { languages } => {
+            let mut normalized: Vec<String> = Vec::new();
+            for language in &languages {
+                let code = normalize_language(language).ok_or_else(|| unknown(language))?;
+                if !normalized.contains(&code) {
+                    normalized.push(code);
+                }
+            }
+            if normalized.is_empty() {
+                return Err("Allowed languages must not be empty".to_string());
+            }
+            LanguageSetting::Allowed {
+                languages: normalized,
+            }
+        }
+    };
+
+    let path = get_language_setting_path(&app)?;
+    let content = serde_json::to_string_pretty(&setting)
+        .map_err(|e| format!("Failed to serialize language setting: {}", e))?;
+
+    fs::write(&path, content).map_err(|e| format!("Failed to write language setting: {}", e))
+}
@@ -1,17 +1,39 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 mod activate;
+mod answer_insert;
 mod api;
+mod app_context;
+mod audio;
+mod auth;
 mod capture;
+mod chunking;
+mod code_runner;
+// Public so the chunk coalescing benchmark can drive it
+pub mod coalesce;
 mod db;
+mod error_rules;
+mod inspector;
+mod language;
+mod outbox;
+mod pipeline;
+mod question;
 mod shortcuts;
+mod structured;
+mod transcription_cache;
+// Public so the VAD evaluation benchmark can drive the detectors
+pub mod vad;
+mod vocabulary;
 mod window;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tauri::{AppHandle, Manager, WebviewWindow};
 use tauri_plugin_posthog::{init as posthog_init, PostHogConfig, PostHogOptions};
 use tokio::task::JoinHandle;
 mod speaker;
 use capture::CaptureState;
-use speaker::VadConfig;
+use speaker::{CaptureSource, VadConfig};
 
 #[cfg(target_os = "macos")]
 #[allow(deprecated)]
@@ -19,9 +41,16 @@ use tauri_nspanel::{cocoa::appkit::NSWindowCollectionBehavior, panel_delegate, W
 
 #[derive(Default)]
 pub struct AudioState {
-    stream_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+    // One task per source, so microphone and system audio can be captured together
+    capture_tasks: Arc<Mutex<HashMap<CaptureSource, JoinHandle<()>>>>,
+    // Sources whose device is being opened, reserved so a second start is refused
+    starting_sources: Arc<Mutex<HashSet<CaptureSource>>>,
+    // Set while a running source is paused; its task keeps reading but emits no utterances
+    pause_flags: Arc<Mutex<HashMap<CaptureSource, Arc<AtomicBool>>>>,
     vad_config: Arc<Mutex<VadConfig>>,
     is_capturing: Arc<Mutex<bool>>,
+    // Zero point of the utterance timeline shared by all running sources
+    timeline_start: Arc<Mutex<Option<Instant>>>,
 }
 
 #[tauri::command]
@@ -41,6 +70,14 @@ pub fn run() {
         )
         .manage(AudioState::default())
         .manage(CaptureState::default())
+        .manage(error_rules::ErrorDiagnosticsState::default())
+        .manage(inspector::InspectorState::default())
+        .manage(outbox::OutboxState::default())
+        .manage(coalesce::CoalesceState::default())
+        .manage(question::QuestionState::default())
+        .manage(pipeline::PipelineState::default())
+        .manage(answer_insert::InsertState::default())
+        .manage(code_runner::CodeRunState::default())
         .manage(shortcuts::WindowVisibility {
             is_hidden: Mutex::new(false),
         })
@@ -100,9 +137,46 @@ pub fn run() {
             api::transcribe_audio,
             api::chat_stream_response,
             api::fetch_models,
+            api::fetch_provider_models,
             api::create_system_prompt,
             activate::check_license_status,
             api::get_activity,
+            auth::get_auth_overrides,
+            auth::save_auth_overrides,
+            error_rules::get_error_rules,
+            error_rules::save_error_rules,
+            error_rules::get_error_diagnostics,
+            error_rules::clear_error_diagnostics,
+            inspector::get_inspector_entries,
+            inspector::export_inspector_entries,
+            inspector::clear_inspector_entries,
+            inspector::get_inspector_config,
+            inspector::update_inspector_config,
+            outbox::get_outbox_items,
+            outbox::retry_outbox_item,
+            outbox::delete_outbox_item,
+            coalesce::get_stream_coalesce_config,
+            coalesce::update_stream_coalesce_config,
+            question::get_question_detection_config,
+            question::save_question_detection_config,
+            question::submit_transcript,
+            language::get_transcription_language,
+            language::save_transcription_language,
+            pipeline::get_pipeline_config,
+            pipeline::update_pipeline_config,
+            vocabulary::get_vocabulary,
+            vocabulary::add_vocabulary_term,
+            vocabulary::delete_vocabulary_term,
+            transcription_cache::clear_transcription_cache,
+            app_context::get_active_app_context,
+            app_context::get_app_context_config,
+            app_context::save_app_context_config,
+            answer_insert::insert_answer,
+            answer_insert::cancel_answer_insert,
+            answer_insert::get_insert_config,
+            answer_insert::save_insert_config,
+            code_runner::run_code_snippet,
+            code_runner::cancel_code_run,
             speaker::start_system_audio_capture,
             speaker::stop_system_audio_capture,
             speaker::manual_stop_continuous,
@@ -112,6 +186,12 @@ pub fn run() {
             speaker::update_vad_config,
             speaker::get_capture_status,
             speaker::get_audio_sample_rate,
+            speaker::list_audio_devices,
+            speaker::pause_capture,
+            speaker::resume_capture,
+            audio::start_microphone_capture,
+            audio::stop_microphone_capture,
+            audio::check_microphone_access,
         ])
         .setup(|app| {
             // Setup main window positioning
@@ -120,6 +200,8 @@ pub fn run() {
             init(app.app_handle());
 
             let app_handle = app.handle();
+            inspector::load_persisted_entries(app_handle);
+            outbox::start_outbox_monitor(app_handle.clone());
             if app_handle.get_webview_window("dashboard").is_none() {
                 if let Err(e) = window::create_dashboard_window(&app_handle) {
                     eprintln!("Failed to create dashboard window on startup: {}", e);
@@ -0,0 +1,387 @@
+// Persistent outbox for chat and transcription requests made while offline.
+// Items live in the `outbox` SQLite table and are replayed in order once the
+// endpoint is reachable again.
+use crate::api::{
+    decode_audio_base64, get_app_endpoint, run_chat_stream, run_transcription, ChatEvents,
+    ChatRequest,
+};
+use crate::db::get_pool;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const RETRY_INTERVAL: Duration = Duration::from_secs(15);
+const IDLE_INTERVAL: Duration = Duration::from_secs(60);
+const PREVIEW_CHARS: usize = 120;
+const OFFLINE_MESSAGE: &str =
+    "You're offline. Your request was saved and will be sent automatically once the connection is back.";
+
+// What was queued. Stored as JSON in the `payload` column.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "request", rename_all = "snake_case")]
+pub enum OutboxPayload {
+    Chat(Box<ChatRequest>),
+    Transcription { audio_base64: String },
+}
+
+impl OutboxPayload {
+    fn kind(&self) -> &'static str {
+        match self {
+            OutboxPayload::Chat(_) => "chat",
+            OutboxPayload::Transcription { .. } => "transcription",
+        }
+    }
+
+    fn preview(&self) -> String {
+        match self {
+            OutboxPayload::Chat(request) => {
+                request.user_message.chars().take(PREVIEW_CHARS).collect()
+            }
+            OutboxPayload::Transcription { audio_base64 } => {
+                format!("Audio ({} KB)", audio_base64.len() * 3 / 4 / 1024)
+            }
+        }
+    }
+}
+
+// Outbox row without the (potentially large) payload
+#[derive(Debug, Clone, Serialize)]
+pub struct OutboxItem {
+    pub id: String,
+    pub kind: String,
+    pub conversation_id: Option<String>,
+    pub preview: String,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+}
+
+type OutboxRow = (
+    String,
+    String,
+    Option<String>,
+    String,
+    String,
+    i64,
+    Option<String>,
+    i64,
+);
+
+impl From<OutboxRow> for OutboxItem {
+    fn from(row: OutboxRow) -> Self {
+        let (id, kind, conversation_id, preview, status, attempts, last_error, created_at) = row;
+        Self {
+            id,
+            kind,
+            conversation_id,
+            preview,
+            status,
+            attempts,
+            last_error,
+            created_at,
+        }
+    }
+}
+
+// Result of a replayed request, tied back to the conversation it came from
+#[derive(Debug, Clone, Serialize)]
+pub struct OutboxEvent {
+    pub id: String,
+    pub kind: String,
+    pub conversation_id: Option<String>,
+    pub preview: String,
+    pub response: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+pub struct OutboxState {
+    wake: Notify,
+    online: AtomicBool,
+}
+
+impl Default for OutboxState {
+    fn default() -> Self {
+        Self {
+            wake: Notify::new(),
+            online: AtomicBool::new(true),
+        }
+    }
+}
+
+// True when the app endpoint answers at all; any HTTP status counts as reachable
+pub async fn is_online() -> bool {
+    let Ok(endpoint) = get_app_endpoint() else {
+        return true;
+    };
+    let Ok(client) = reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() else {
+        return true;
+    };
+
+    client.head(&endpoint).send().await.is_ok()
+}
+
+fn set_online(app: &AppHandle, online: bool) {
+    let state = app.state::<OutboxState>();
+    if state.online.swap(online, Ordering::SeqCst) != online {
+        tracing::info!("Connectivity changed: online = {}", online);
+        let _ = app.emit("connectivity-changed", online);
+    }
+}
+
+async fn enqueue(
+    app: &AppHandle,
+    conversation_id: Option<String>,
+    payload: &OutboxPayload,
+) -> Result<OutboxItem, String> {
+    let pool = get_pool(app).await?;
+    let payload_json = serde_json::to_string(payload)
+        .map_err(|e| format!("Failed to serialize outbox payload: {}", e))?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let item = OutboxItem {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind: payload.kind().to_string(),
+        conversation_id,
+        preview: payload.preview(),
+        status: "pending".to_string(),
+        attempts: 0,
+        last_error: None,
+        created_at: now,
+    };
+
+    sqlx::query(
+        "INSERT INTO outbox (id, kind, conversation_id, preview, payload, status, attempts, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, 'pending', 0, ?, ?)",
+    )
+    .bind(&item.id)
+    .bind(&item.kind)
+    .bind(&item.conversation_id)
+    .bind(&item.preview)
+    .bind(payload_json)
+    .bind(now)
+    .bind(now)
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Failed to queue request: {}", e))?;
+
+    Ok(item)
+}
+
+// Called when a request failed. If the endpoint is unreachable the request is stored
+// for later and a friendlier message is returned; otherwise the original error is kept.
+pub async fn queue_if_offline(
+    app: &AppHandle,
+    conversation_id: Option<String>,
+    payload: OutboxPayload,
+    error: String,
+) -> String {
+    if is_online().await {
+        return error;
+    }
+    set_online(app, false);
+
+    match enqueue(app, conversation_id, &payload).await {
+        Ok(item) => {
+            let _ = app.emit("outbox-queued", &item);
+            app.state::<OutboxState>().wake.notify_one();
+            OFFLINE_MESSAGE.to_string()
+        }
+        Err(e) => {
+            tracing::warn!("Failed to queue offline request: {}", e);
+            error
+        }
+    }
+}
+
+async fn update_status(
+    pool: &Pool<Sqlite>,
+    id: &str,
+    status: &str,
+    last_error: Option<&str>,
+) -> Result<(), String> {
+    sqlx::query("UPDATE outbox SET status = ?, last_error = ?, updated_at = ? WHERE id = ?")
+        .bind(status)
+        .bind(last_error)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .bind(id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to update outbox item: {}", e))
+}
+
+async fn pending_count(pool: &Pool<Sqlite>) -> Result<i64, String> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM outbox WHERE status = 'pending'")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to read outbox: {}", e))
+}
+
+async fn replay_payload(
+    app: &AppHandle,
+    payload: OutboxPayload,
+) -> Result<serde_json::Value, String> {
+    match payload {
+        OutboxPayload::Chat(request) => run_chat_stream(app.clone(), *request, ChatEvents::Silent)
+            .await
+            .map(serde_json::Value::String),
+        OutboxPayload::Transcription { audio_base64 } => {
+            let audio_bytes = decode_audio_base64(&audio_base64)?;
+            let response = run_transcription(app.clone(), audio_bytes).await?;
+            serde_json::to_value(response)
+                .map_err(|e| format!("Failed to serialize transcription: {}", e))
+        }
+    }
+}
+
+// Replays pending items oldest first. Stops at the first network failure so order is kept;
+// items rejected by the provider are marked failed and skipped.
+async fn deliver_pending(app: &AppHandle, pool: &Pool<Sqlite>) -> Result<(), String> {
+    let rows: Vec<(String, String, Option<String>, String, String)> = sqlx::query_as(
+        "SELECT id, kind, conversation_id, preview, payload FROM outbox
+         WHERE status = 'pending' ORDER BY created_at ASC, rowid ASC",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to read outbox: {}", e))?;
+
+    for (id, kind, conversation_id, preview, payload) in rows {
+        let payload: OutboxPayload = match serde_json::from_str(&payload) {
+            Ok(payload) => payload,
+            Err(e) => {
+                update_status(
+                    pool,
+                    &id,
+                    "failed",
+                    Some(&format!("Corrupt payload: {}", e)),
+                )
+                .await?;
+                continue;
+            }
+        };
+
+        sqlx::query(
+            "UPDATE outbox SET status = 'sending', attempts = attempts + 1, updated_at = ? WHERE id = ?",
+        )
+        .bind(chrono::Utc::now().timestamp_millis())
+        .bind(&id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to update outbox item: {}", e))?;
+
+        let result = replay_payload(app, payload).await;
+
+        let mut event = OutboxEvent {
+            id: id.clone(),
+            kind,
+            conversation_id,
+            preview,
+            response: None,
+            error: None,
+        };
+
+        match result {
+            Ok(response) => {
+                sqlx::query("DELETE FROM outbox WHERE id = ?")
+                    .bind(&id)
+                    .execute(pool)
+                    .await
+                    .map_err(|e| format!("Failed to remove outbox item: {}", e))?;
+                event.response = Some(response);
+                let _ = app.emit("outbox-delivered", &event);
+            }
+            Err(error) => {
+                if !is_online().await {
+                    update_status(pool, &id, "pending", Some(&error)).await?;
+                    set_online(app, false);
+                    break;
+                }
+                update_status(pool, &id, "failed", Some(&error)).await?;
+                event.error = Some(error);
+                let _ = app.emit("outbox-failed", &event);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Background task: waits for connectivity and drains the outbox. Woken early whenever
+// something is queued or retried.
+pub fn start_outbox_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut recovered = false;
+
+        loop {
+            let mut wait = IDLE_INTERVAL;
+
+            match get_pool(&app).await {
+                Ok(pool) => {
+                    // Items left mid-send by a previous run go back in line
+                    if !recovered {
+                        recovered = sqlx::query(
+                            "UPDATE outbox SET status = 'pending' WHERE status = 'sending'",
+                        )
+                        .execute(&pool)
+                        .await
+                        .is_ok();
+                    }
+
+                    if pending_count(&pool).await.unwrap_or(0) > 0 {
+                        let online = is_online().await;
+                        set_online(&app, online);
+                        if online {
+                            if let Err(e) = deliver_pending(&app, &pool).await {
+                                tracing::warn!("Outbox delivery failed: {}", e);
+                            }
+                        }
+                        if pending_count(&pool).await.unwrap_or(0) > 0 {
+                            wait = RETRY_INTERVAL;
+                        }
+                    }
+                }
+                Err(_) => wait = RETRY_INTERVAL,
+            }
+
+            let state = app.state::<OutboxState>();
+            let _ = tokio::time::timeout(wait, state.wake.notified()).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_outbox_items(app: AppHandle) -> Result<Vec<OutboxItem>, String> {
+    let pool = get_pool(&app).await?;
+    let rows: Vec<OutboxRow> = sqlx::query_as(
+        "SELECT id, kind, conversation_id, preview, status, attempts, last_error, created_at
+         FROM outbox ORDER BY created_at ASC, rowid ASC",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to read outbox: {}", e))?;
+
+    Ok(rows.into_iter().map(OutboxItem::from).collect())
+}
+
+#[tauri::command]
+pub async fn retry_outbox_item(app: AppHandle, id: String) -> Result<(), String> {
+    let pool = get_pool(&app).await?;
+    update_status(&pool, &id, "pending", None).await?;
+    app.state::<OutboxState>().wake.notify_one();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_outbox_item(app: AppHandle, id: String) -> Result<(), String> {
+    let pool = get_pool(&app).await?;
+    sqlx::query("DELETE FROM outbox WHERE id = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to delete outbox item: {}", e))
+}
@@ -0,0 +1,333 @@
+// Backend speech pipeline: utterance -> transcription -> optional answer, without sending
+// audio through the webview. Progress for each utterance is reported on `utterance-progress`.
+use crate::answer_insert::record_answer;
+use crate::api::{run_chat_stream, run_transcription, ChatEvents, ChatRequest};
+use crate::question::{evaluate_transcript, load_question_config, AnswerStatus, QuestionDetection};
+use crate::speaker::CaptureSource;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::oneshot;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerMode {
+    // Only transcribe
+    Never,
+    // Answer transcripts the question detector accepts
+    Questions,
+    // Answer every transcript
+    Always,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineConfig {
+    // When false, utterances are emitted to the webview as `speech-detected` like before
+    pub enabled: bool,
+    pub answer_mode: AnswerMode,
+    pub system_prompt: Option<String>,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            answer_mode: AnswerMode::Questions,
+            system_prompt: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum PipelineStage {
+    Transcribing,
+    Transcribed {
+        text: String,
+        language: Option<String>,
+        question: Option<QuestionDetection>,
+    },
+    Answering,
+    Done {
+        text: String,
+        answer: Option<String>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+// Identifies an utterance in progress events
+#[derive(Debug, Clone, Serialize)]
+pub struct UtteranceInfo {
+    pub id: String,
+    pub source: CaptureSource,
+    // Start on the capture timeline shared by microphone and system audio
+    pub timeline_ms: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UtteranceProgress {
+    #[serde(flatten)]
+    pub utterance: UtteranceInfo,
+    #[serde(flatten)]
+    pub stage: PipelineStage,
+}
+
+// Hands out turns in submission order. Each turn ends after the one before it, whether it
+// was used, skipped (empty transcript, nothing to answer) or cut short by an error.
+#[derive(Default)]
+struct TurnQueue {
+    last: Mutex<Option<oneshot::Receiver<()>>>,
+}
+
+struct Turn {
+    previous: Option<oneshot::Receiver<()>>,
+    done: Option<oneshot::Sender<()>>,
+}
+
+impl TurnQueue {
+    fn reserve(&self) -> Turn {
+        let (done, next) = oneshot::channel();
+        let mut last = match self.last.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        Turn {
+            previous: last.replace(next),
+            done: Some(done),
+        }
+    }
+}
+
+impl Turn {
+    async fn wait(&mut self) {
+        if let Some(previous) = self.previous.as_mut() {
+            let _ = previous.await;
+            self.previous = None;
+        }
+    }
+
+    // Same as dropping the turn; early returns and errors end it the same way
+    fn finish(self) {
+        drop(self);
+    }
+}
+
+impl Drop for Turn {
+    fn drop(&mut self) {
+        let Some(done) = self.done.take() else {
+            return;
+        };
+        match self.previous.take() {
+            // Not our turn yet; pass it on once the previous one ends
+            Some(previous) => {
+                tauri::async_runtime::spawn(async move {
+                    let _ = previous.await;
+                    let _ = done.send(());
+                });
+            }
+            None => {
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PipelineState {
+    config: Mutex<PipelineConfig>,
+    // Transcriptions run concurrently; the answer decision and the answer itself are taken
+    // in speaking order, each stage with its own queue so transcripts aren't held up by a
+    // previous answer
+    decisions: TurnQueue,
+    answers: TurnQueue,
+}
+
+pub fn load_pipeline_config(app: &AppHandle) -> PipelineConfig {
+    let state = app.state::<PipelineState>();
+    let config = match state.config.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    config.clone()
+}
+
+fn emit_progress(app: &AppHandle, utterance: &UtteranceInfo, stage: PipelineStage) {
+    let progress = UtteranceProgress {
+        utterance: utterance.clone(),
+        stage,
+    };
+    let _ = app.emit("utterance-progress", &progress);
+}
+
+// Decides whether the transcript should be answered, reusing the question detector's
+// classifier, cooldown and dedup in `Questions` mode
+async fn should_answer(
+    app: &AppHandle,
+    mode: AnswerMode,
+    source: CaptureSource,
+    text: &str,
+) -> (bool, Option<QuestionDetection>) {
+    // The user's own speech is transcribed for the timeline but never answered
+    if source == CaptureSource::Me {
+        return (false, None);
+    }
+
+    match mode {
+        AnswerMode::Never => (false, None),
+        AnswerMode::Always => (true, None),
+        AnswerMode::Questions => {
+            let mut config = load_question_config(app);
+            config.enabled = true;
+            let detection = evaluate_transcript(app, &config, text).await;
+            let answer = detection.answer_status == AnswerStatus::Triggered;
+            (answer, Some(detection))
+        }
+    }
+}
+
+async fn process_utterance(
+    app: &AppHandle,
+    config: &PipelineConfig,
+    utterance: &UtteranceInfo,
+    wav_bytes: Vec<u8>,
+    mut decision_turn: Turn,
+    mut answer_turn: Turn,
+) -> Result<(String, Option<String>), String> {
+    emit_progress(app, utterance, PipelineStage::Transcribing);
+    let response = run_transcription(app.clone(), wav_bytes).await?;
+    let text = response
+        .transcription
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    if text.is_empty() {
+        return Ok((text, None));
+    }
+
+    // Cooldown and dedup in the question detector depend on seeing transcripts in order
+    decision_turn.wait().await;
+    let (answer, question) = should_answer(app, config.answer_mode, utterance.source, &text).await;
+    decision_turn.finish();
+    emit_progress(
+        app,
+        utterance,
+        PipelineStage::Transcribed {
+            text: text.clone(),
+            language: response.language,
+            question,
+        },
+    );
+    if !answer {
+        return Ok((text, None));
+    }
+
+    answer_turn.wait().await;
+    emit_progress(app, utterance, PipelineStage::Answering);
+    let request = ChatRequest {
+        user_message: text.clone(),
+        system_prompt: config.system_prompt.clone(),
+        image_base64: None,
+        history: None,
+        structured_output: None,
+        app_context: None,
+    };
+    let events = ChatEvents::Named {
+        chunk: "utterance_answer_chunk",
+        complete: "utterance_answer_complete",
+    };
+    let answer = run_chat_stream(app.clone(), request, events).await?;
+    record_answer(app, &answer);
+    answer_turn.finish();
+
+    Ok((text, Some(answer)))
+}
+
+// Hands a captured utterance (16-bit mono WAV) to the pipeline. Returns immediately; the
+// utterance's id identifies it in `utterance-progress` events.
+pub fn submit_utterance(app: &AppHandle, wav_bytes: Vec<u8>, utterance: UtteranceInfo) {
+    let config = load_pipeline_config(app);
+    // Turns are reserved here, in the order utterances were captured
+    let state = app.state::<PipelineState>();
+    let decision_turn = state.decisions.reserve();
+    let answer_turn = state.answers.reserve();
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = process_utterance(
+            &app,
+            &config,
+            &utterance,
+            wav_bytes,
+            decision_turn,
+            answer_turn,
+        )
+        .await;
+        let stage = match result {
+            Ok((text, answer)) => PipelineStage::Done { text, answer },
+            Err(message) => {
+                tracing::warn!("Utterance pipeline failed: {}", message);
+                PipelineStage::Error { message }
+            }
+        };
+        emit_progress(&app, &utterance, stage);
+    });
+}
+
+#[tauri::command]
+pub fn get_pipeline_config(app: AppHandle) -> Result<PipelineConfig, String> {
+    Ok(load_pipeline_config(&app))
+}
+
+#[tauri::command]
+pub fn update_pipeline_config(app: AppHandle, config: PipelineConfig) -> Result<(), String> {
+    let state = app.state::<PipelineState>();
+    *state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to update pipeline config: {}", e))? = config;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn finishes_soon(turn: &mut Turn) -> bool {
+        tokio::time::timeout(Duration::from_millis(100), turn.wait())
+            .await
+            .is_ok()
+    }
+
+    #[tokio::test]
+    async fn skipped_turn_still_waits_for_the_one_before() {
+        let queue = TurnQueue::default();
+        let mut first = queue.reserve();
+        let second = queue.reserve();
+        let mut third = queue.reserve();
+
+        // The middle utterance had nothing to answer and never took its turn
+        drop(second);
+        assert!(!finishes_soon(&mut third).await);
+
+        first.wait().await;
+        first.finish();
+        assert!(finishes_soon(&mut third).await);
+    }
+
+    #[tokio::test]
+    async fn turns_end_in_order_when_all_are_skipped() {
+        let queue = TurnQueue::default();
+        let first = queue.reserve();
+        let second = queue.reserve();
+        let mut third = queue.reserve();
+
+        drop(second);
+        drop(first);
+        assert!(finishes_soon(&mut third).await);
+    }
+}
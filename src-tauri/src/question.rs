@@ -0,0 +1,570 @@
+// Detects questions in system-audio transcripts and answers them through the chat pipeline.
+// This is backend-only for now: no screen enables it or shows the answers. It is switched
+// on through `save_question_detection_config`, and answers stream on the `auto_answer_*`
+// events for whichever client listens to them.
+use crate::api::{run_chat_stream, ChatEvents, ChatRequest};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+const QUESTION_PLACEHOLDER: &str = "{question}";
+const DUPLICATE_SIMILARITY: f32 = 0.8;
+// Rule scores in this band are ambiguous enough to ask the LLM (when enabled)
+const LLM_BAND: (f32, f32) = (0.25, 0.85);
+
+const INTERROGATIVES: &[&str] = &[
+    "what", "why", "how", "when", "where", "which", "who", "whom", "whose",
+];
+
+const AUXILIARY_OPENERS: &[&str] = &[
+    "can you",
+    "could you",
+    "would you",
+    "will you",
+    "do you",
+    "did you",
+    "have you",
+    "are you",
+    "were you",
+    "should you",
+    "is it",
+    "is there",
+    "are there",
+    "does it",
+    "do we",
+    "can we",
+    "should we",
+    "would it",
+    "have we",
+    "is that",
+    "was it",
+];
+
+// Imperatives that are questions in all but punctuation, when they open the sentence
+// ("Explain X" is a request, "let me explain X" is not)
+const REQUEST_OPENERS: &[&str] = &[
+    "tell me",
+    "tell us",
+    "walk me through",
+    "walk us through",
+    "explain",
+    "describe",
+    "give me an example",
+    "give an example",
+    "talk about",
+    "talk me through",
+    "share an example",
+];
+
+// Words that may precede a request opener ("so, please explain...")
+const REQUEST_LEAD_INS: &[&str] = &["so", "please", "now", "okay", "ok", "and", "then"];
+
+// Requests addressed to the listener anywhere in the transcript, matched as whole words
+const REQUEST_PHRASES: &[&str] = &[
+    "how would you",
+    "what would you",
+    "how do you",
+    "what is your",
+    "what's your",
+    "i'd like to hear",
+    "i would like to hear",
+];
+
+// Tag questions at the end of a sentence ("..., right?")
+const TAG_ENDINGS: &[&str] = &[
+    "right",
+    "correct",
+    "isn't it",
+    "aren't you",
+    "don't you",
+    "didn't you",
+    "wouldn't you",
+    "can't you",
+    "okay",
+    "yeah",
+];
+
+const FILLERS: &[&str] = &["you know", "right", "okay", "yeah", "huh", "what", "sorry"];
+
+const DEFAULT_ANSWER_PROMPT: &str = "The interviewer just asked: \"{question}\"\n\
+Answer it as me, concisely and in a way I can say out loud.";
+
+const LLM_CLASSIFIER_PROMPT: &str = "You classify snippets of a conversation transcript. \
+Reply with only YES if the snippet is a question or request directed at the listener, \
+otherwise reply with only NO.";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionDetectionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub use_llm: bool,
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f32,
+    // `{question}` is replaced with the transcript; without it the question is appended
+    #[serde(default = "default_answer_prompt")]
+    pub answer_prompt: String,
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+    #[serde(default = "default_dedup_window_secs")]
+    pub dedup_window_secs: u64,
+}
+
+fn default_min_confidence() -> f32 {
+    0.6
+}
+
+fn default_answer_prompt() -> String {
+    DEFAULT_ANSWER_PROMPT.to_string()
+}
+
+fn default_cooldown_secs() -> u64 {
+    15
+}
+
+fn default_dedup_window_secs() -> u64 {
+    120
+}
+
+impl Default for QuestionDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            use_llm: false,
+            min_confidence: default_min_confidence(),
+            answer_prompt: default_answer_prompt(),
+            system_prompt: None,
+            cooldown_secs: default_cooldown_secs(),
+            dedup_window_secs: default_dedup_window_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuestionClassification {
+    pub is_question: bool,
+    pub confidence: f32,
+    pub reasons: Vec<String>,
+    pub used_llm: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerStatus {
+    Triggered,
+    Disabled,
+    NotAQuestion,
+    Cooldown,
+    Duplicate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuestionDetection {
+    pub text: String,
+    pub classification: QuestionClassification,
+    pub answer_status: AnswerStatus,
+    pub answer_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AutoAnswerEvent {
+    id: String,
+    question: String,
+    error: Option<String>,
+}
+
+#[derive(Default)]
+struct AnswerHistory {
+    last_answer_at: Option<Instant>,
+    recent: VecDeque<(Instant, HashSet<String>)>,
+}
+
+#[derive(Default)]
+pub struct QuestionState {
+    history: Mutex<AnswerHistory>,
+}
+
+fn normalized_words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_string())
+        .collect()
+}
+
+fn starts_with_phrase(normalized: &str, phrase: &str) -> bool {
+    normalized == phrase || normalized.starts_with(&format!("{} ", phrase))
+}
+
+// Whole-word match, so "explain" doesn't match "explained"
+fn contains_phrase(normalized: &str, phrase: &str) -> bool {
+    format!(" {} ", normalized).contains(&format!(" {} ", phrase))
+}
+
+fn request_opener(words: &[String]) -> Option<&'static str> {
+    let start = words
+        .iter()
+        .take_while(|word| REQUEST_LEAD_INS.contains(&word.as_str()))
+        .count();
+    let rest = words[start..].join(" ");
+    REQUEST_OPENERS
+        .iter()
+        .find(|opener| starts_with_phrase(&rest, opener))
+        .copied()
+}
+
+// Rule-based score from wording and punctuation. Transcription engines mark rising
+// intonation with a trailing `?`, so punctuation carries most of the prosody signal.
+pub fn classify_with_rules(text: &str, min_confidence: f32) -> QuestionClassification {
+    let words = normalized_words(text);
+    let normalized = words.join(" ");
+    let trimmed = text.trim();
+    let mut score: f32 = 0.0;
+    let mut reasons = Vec::new();
+
+    if words.is_empty() || FILLERS.contains(&normalized.as_str()) {
+        return QuestionClassification {
+            is_question: false,
+            confidence: 0.0,
+            reasons: vec!["filler".to_string()],
+            used_llm: false,
+        };
+    }
+
+    // Only the last sentence decides "rising" punctuation; earlier ones may be context
+    let last_sentence = trimmed
+        .rsplit(['.', '!', '?'])
+        .find(|part| !part.trim().is_empty())
+        .unwrap_or(trimmed)
+        .trim();
+    let last_words = normalized_words(last_sentence);
+    let last_normalized = last_words.join(" ");
+
+    if trimmed.ends_with('?') {
+        score += 0.55;
+        reasons.push("ends with question mark".to_string());
+
+        if TAG_ENDINGS
+            .iter()
+            .any(|tag| last_normalized.ends_with(tag) && last_words.len() > 3)
+        {
+            score -= 0.15;
+            reasons.push("tag question".to_string());
+        }
+    } else if trimmed.contains('?') {
+        score += 0.3;
+        reasons.push("contains question mark".to_string());
+    }
+
+    // "what's", "how'd"... count as their interrogative
+    if let Some(word) = last_words
+        .first()
+        .and_then(|word| word.split('\'').next())
+        .filter(|word| INTERROGATIVES.contains(word))
+    {
+        score += 0.45;
+        reasons.push(format!("starts with \"{}\"", word));
+    } else if let Some(opener) = AUXILIARY_OPENERS
+        .iter()
+        .find(|opener| starts_with_phrase(&last_normalized, opener))
+    {
+        score += 0.45;
+        reasons.push(format!("inverted opener \"{}\"", opener));
+    }
+
+    if let Some(phrase) = request_opener(&last_words).or_else(|| {
+        REQUEST_PHRASES
+            .iter()
+            .find(|phrase| contains_phrase(&normalized, phrase))
+            .copied()
+    }) {
+        score += 0.5;
+        reasons.push(format!("request phrase \"{}\"", phrase));
+    }
+
+    if words
+        .iter()
+        .any(|word| matches!(word.as_str(), "you" | "your" | "you're" | "you've"))
+    {
+        score += 0.1;
+        reasons.push("addressed to listener".to_string());
+    }
+
+    if words.len() < 3 {
+        score -= 0.3;
+        reasons.push("very short".to_string());
+    } else if words.len() > 80 {
+        score -= 0.1;
+        reasons.push("very long".to_string());
+    }
+
+    let confidence = score.clamp(0.0, 1.0);
+    QuestionClassification {
+        is_question: confidence >= min_confidence,
+        confidence,
+        reasons,
+        used_llm: false,
+    }
+}
+
+// Asks the configured model for a YES/NO verdict. Falls back to the rules on any error.
+async fn classify_with_llm(
+    app: &AppHandle,
+    text: &str,
+    mut rules: QuestionClassification,
+) -> QuestionClassification {
+    let request = ChatRequest {
+        user_message: text.to_string(),
+        system_prompt: Some(LLM_CLASSIFIER_PROMPT.to_string()),
+        image_base64: None,
+        history: None,
+        structured_output: None,
+        app_context: None,
+    };
+
+    match run_chat_stream(app.clone(), request, ChatEvents::Silent).await {
+        Ok(verdict) => {
+            let verdict = verdict.trim().to_lowercase();
+            rules.used_llm = true;
+            rules.is_question = verdict.starts_with("yes");
+            rules.reasons.push(format!("llm: {}", verdict));
+            rules
+        }
+        Err(e) => {
+            tracing::warn!("Question classification via LLM failed: {}", e);
+            rules
+        }
+    }
+}
+
+fn word_similarity(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+// Applies cooldown and dedup, and reserves the slot when the answer may go ahead
+fn check_and_reserve(
+    app: &AppHandle,
+    config: &QuestionDetectionConfig,
+    text: &str,
+) -> AnswerStatus {
+    let state = app.state::<QuestionState>();
+    let mut history = match state.history.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let now = Instant::now();
+
+    if history
+        .last_answer_at
+        .is_some_and(|at| now.duration_since(at) < Duration::from_secs(config.cooldown_secs))
+    {
+        return AnswerStatus::Cooldown;
+    }
+
+    let dedup_window = Duration::from_secs(config.dedup_window_secs);
+    history
+        .recent
+        .retain(|(at, _)| now.duration_since(*at) < dedup_window);
+
+    let words: HashSet<String> = normalized_words(text).into_iter().collect();
+    if history
+        .recent
+        .iter()
+        .any(|(_, previous)| word_similarity(previous, &words) >= DUPLICATE_SIMILARITY)
+    {
+        return AnswerStatus::Duplicate;
+    }
+
+    history.last_answer_at = Some(now);
+    history.recent.push_back((now, words));
+    AnswerStatus::Triggered
+}
+
+fn build_answer_prompt(template: &str, question: &str) -> String {
+    if template.contains(QUESTION_PLACEHOLDER) {
+        template.replace(QUESTION_PLACEHOLDER, question)
+    } else {
+        format!("{}\n\n{}", template.trim_end(), question)
+    }
+}
+
+fn spawn_auto_answer(app: &AppHandle, config: &QuestionDetectionConfig, question: &str) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    let request = ChatRequest {
+        user_message: build_answer_prompt(&config.answer_prompt, question),
+        system_prompt: config.system_prompt.clone(),
+        image_base64: None,
+        history: None,
+        structured_output: None,
+        app_context: None,
+    };
+    let mut event = AutoAnswerEvent {
+        id: id.clone(),
+        question: question.to_string(),
+        error: None,
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let _ = app.emit("auto_answer_started", &event);
+        let events = ChatEvents::Named {
+            chunk: "auto_answer_chunk",
+            complete: "auto_answer_complete",
+        };
+        if let Err(e) = run_chat_stream(app.clone(), request, events).await {
+            event.error = Some(e);
+            let _ = app.emit("auto_answer_error", &event);
+        }
+    });
+
+    id
+}
+
+fn get_question_config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+    Ok(app_data_dir.join("question_detection.json"))
+}
+
+pub fn load_question_config(app: &AppHandle) -> QuestionDetectionConfig {
+    let Ok(path) = get_question_config_path(app) else {
+        return QuestionDetectionConfig::default();
+    };
+
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// Classifies a transcript and applies cooldown/dedup. `Triggered` means an answer should
+// be generated; the caller decides how.
+pub async fn evaluate_transcript(
+    app: &AppHandle,
+    config: &QuestionDetectionConfig,
+    text: &str,
+) -> QuestionDetection {
+    let text = text.trim();
+
+    let mut classification = classify_with_rules(text, config.min_confidence);
+    if config.enabled
+        && config.use_llm
+        && (LLM_BAND.0..LLM_BAND.1).contains(&classification.confidence)
+    {
+        classification = classify_with_llm(app, text, classification).await;
+    }
+
+    let answer_status = if !config.enabled {
+        AnswerStatus::Disabled
+    } else if !classification.is_question {
+        AnswerStatus::NotAQuestion
+    } else {
+        check_and_reserve(app, config, text)
+    };
+
+    QuestionDetection {
+        text: text.to_string(),
+        classification,
+        answer_status,
+        answer_id: None,
+    }
+}
+
+// Classifies a transcript and, if it is a question addressed to the user, starts an
+// answer in the background. Emits `question-detected` either way.
+pub async fn handle_transcript(app: &AppHandle, text: &str) -> QuestionDetection {
+    let config = load_question_config(app);
+    let mut detection = evaluate_transcript(app, &config, text).await;
+
+    if detection.answer_status == AnswerStatus::Triggered {
+        detection.answer_id = Some(spawn_auto_answer(app, &config, &detection.text));
+    }
+
+    let _ = app.emit("question-detected", &detection);
+    detection
+}
+
+#[tauri::command]
+pub fn get_question_detection_config(app: AppHandle) -> Result<QuestionDetectionConfig, String> {
+    Ok(load_question_config(&app))
+}
+
+// The only switch for auto-answer; there is no settings UI for it yet
+#[tauri::command]
+pub fn save_question_detection_config(
+    app: AppHandle,
+    config: QuestionDetectionConfig,
+) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&config.min_confidence) {
+        return Err("Invalid min_confidence: must be 0.0-1.0".to_string());
+    }
+    if config.answer_prompt.trim().is_empty() {
+        return Err("Answer prompt cannot be empty".to_string());
+    }
+
+    let path = get_question_config_path(&app)?;
+    let content = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("Failed to serialize question detection config: {}", e))?;
+
+    fs::write(&path, content)
+        .map_err(|e| format!("Failed to write question detection config: {}", e))
+}
+
+// For transcripts produced outside the backend (e.g. custom STT providers)
+#[tauri::command]
+pub async fn submit_transcript(app: AppHandle, text: String) -> Result<QuestionDetection, String> {
+    if text.trim().is_empty() {
+        return Err("Transcript is empty".to_string());
+    }
+    Ok(handle_transcript(&app, &text).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_question(text: &str) -> bool {
+        classify_with_rules(text, default_min_confidence()).is_question
+    }
+
+    #[test]
+    fn requests_are_questions() {
+        assert!(is_question("Explain how your caching layer works."));
+        assert!(is_question("So, please describe your last project."));
+        assert!(is_question("Walk me through the design of your system."));
+        assert!(is_question("I'd like to hear how you would scale it."));
+        assert!(is_question("What is your favourite language?"));
+    }
+
+    #[test]
+    fn statements_with_request_words_are_not() {
+        assert!(!is_question("Let me explain how your account gets billed."));
+        assert!(!is_question("I explained how your team ships releases."));
+        assert!(!is_question(
+            "The data is described in your onboarding document."
+        ));
+        assert!(!is_question("We will talk about your schedule tomorrow."));
+    }
+
+    #[test]
+    fn fillers_are_not_questions() {
+        assert!(!is_question("Right?"));
+        assert!(!is_question("you know"));
+    }
+}
@@ -1,10 +1,16 @@
 // Pluely AI Speech Detection, and capture system audio (speaker output) as a stream of f32 samples.
-use crate::speaker::SpeakerInput;
+use crate::pipeline::UtteranceInfo;
+use crate::speaker::resample::{ResampleQuality, ResampledStream};
+use crate::speaker::{list_output_devices, AudioDevice, SpeakerInput};
+use crate::vad::{
+    apply_noise_gate, audio_metrics, EnergyVad, GmmVad, NoiseFloorTracker, VadKind, VoiceDetector,
+};
 use anyhow::Result;
 use base64::{engine::general_purpose::STANDARD as B64, Engine as _};
-use futures_util::StreamExt;
+use futures_util::{Stream, StreamExt};
 use hound::{WavSpec, WavWriter};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::collections::VecDeque;
 use std::io::Cursor;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -26,6 +32,33 @@ pub struct VadConfig {
     pub pre_speech_chunks: usize,
     pub noise_gate_threshold: f32,
     pub max_recording_duration_secs: u64,
+    // Rate utterances are analysed and encoded at, e.g. 16000 for speech models. `None`
+    // keeps the device rate. `hop_size` is in samples at the device rate and is rescaled
+    // when resampling, so hop and chunk-count durations don't depend on this.
+    #[serde(default)]
+    pub output_sample_rate: Option<u32>,
+    #[serde(default)]
+    pub resample_quality: ResampleQuality,
+    #[serde(default)]
+    pub detector: VadKind,
+    // Silero VAD .onnx file, required by the neural detector
+    #[serde(default)]
+    pub neural_model_path: Option<String>,
+    // Energy detector only: set the thresholds `threshold_margin_db` above the tracked
+    // noise floor, in quiet and loud rooms alike. `sensitivity_rms`/`peak_threshold` apply
+    // until the floor is known, and when this is off.
+    #[serde(default = "default_adaptive_threshold")]
+    pub adaptive_threshold: bool,
+    #[serde(default = "default_threshold_margin_db")]
+    pub threshold_margin_db: f32,
+}
+
+fn default_adaptive_threshold() -> bool {
+    true
+}
+
+fn default_threshold_margin_db() -> f32 {
+    3.0
 }
 
 impl Default for VadConfig {
@@ -40,8 +73,159 @@ impl Default for VadConfig {
             pre_speech_chunks: 12,  // ~0.27s - enough to catch word start
             noise_gate_threshold: 0.003, // Stronger noise filtering
             max_recording_duration_secs: 180, // 3 minutes default
+            output_sample_rate: None,
+            resample_quality: ResampleQuality::default(),
+            detector: VadKind::default(),
+            neural_model_path: None,
+            adaptive_threshold: default_adaptive_threshold(),
+            threshold_margin_db: default_threshold_margin_db(),
+        }
+    }
+}
+
+// Who is speaking: the user's microphone ("me") or the other party on system audio ("them")
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureSource {
+    Me,
+    Them,
+}
+
+impl CaptureSource {
+    fn label(self) -> &'static str {
+        match self {
+            CaptureSource::Me => "microphone",
+            CaptureSource::Them => "system audio",
+        }
+    }
+}
+
+// Describes a captured utterance. `timeline_ms` is measured from the start of the first
+// running capture, so utterances from both sources can be merged in speaking order;
+// `started_at`/`ended_at` are wall-clock Unix milliseconds.
+#[derive(Debug, Clone, Serialize)]
+struct UtteranceMetadata {
+    id: String,
+    source: CaptureSource,
+    timeline_ms: u64,
+    started_at: i64,
+    ended_at: i64,
+    duration_ms: u64,
+    sample_rate: u32,
+    // Levels of the captured audio, before normalization
+    peak: f32,
+    rms: f32,
+    // Cut at the length cap (30 s with VAD, `max_recording_duration_secs` without) or
+    // by a pause instead of ending in silence
+    force_cut: bool,
+    // Audio from before speech was detected, included at the start
+    pre_speech_ms: u64,
+}
+
+// Payload of `speech-detected`
+#[derive(Debug, Clone, Serialize)]
+struct SpeechDetected {
+    audio: String,
+    #[serde(flatten)]
+    metadata: UtteranceMetadata,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum DiscardReason {
+    // Less speech than `min_speech_chunks` before the silence timeout
+    TooShort,
+    // The audio stream ended in the middle of an utterance
+    StreamEnded,
+}
+
+// Payload of `speech-discarded`
+#[derive(Debug, Clone, Serialize)]
+struct SpeechDiscarded {
+    source: CaptureSource,
+    reason: DiscardReason,
+    timeline_ms: u64,
+    duration_ms: u64,
+    // Hops classified as speech, as a duration
+    speech_ms: u64,
+}
+
+// Payload of `noise-floor`, sent about once a second during VAD capture. Levels are RMS
+// (dBFS for `floor_db`); `threshold_rms` is set when the detector uses an RMS threshold.
+#[derive(Debug, Clone, Serialize)]
+struct NoiseFloor {
+    source: CaptureSource,
+    floor_rms: f32,
+    floor_db: f32,
+    threshold_rms: Option<f32>,
+}
+
+// Payload of `audio-level`, a meter of what the capture receives. Levels are taken before
+// the noise gate; `speech` is the VAD decision and is null when VAD is off.
+#[derive(Debug, Clone, Serialize)]
+struct AudioLevel {
+    source: CaptureSource,
+    rms: f32,
+    peak: f32,
+    speech: Option<bool>,
+    // Whether any sample reached the noise gate threshold
+    gate_open: bool,
+}
+
+const LEVEL_INTERVAL: Duration = Duration::from_millis(50);
+
+// Combines hops into `audio-level` events, at most one per `LEVEL_INTERVAL`
+struct LevelMeter {
+    source: CaptureSource,
+    gate_threshold: f32,
+    sum_squares: f32,
+    samples: usize,
+    peak: f32,
+    speech: Option<bool>,
+    last_emit: Instant,
+}
+
+impl LevelMeter {
+    fn new(source: CaptureSource, gate_threshold: f32) -> Self {
+        Self {
+            source,
+            gate_threshold,
+            sum_squares: 0.0,
+            samples: 0,
+            peak: 0.0,
+            speech: None,
+            last_emit: Instant::now(),
         }
     }
+
+    fn add(&mut self, app: &AppHandle, hop: &[f32], speech: Option<bool>) {
+        if hop.is_empty() {
+            return;
+        }
+        let (rms, peak) = audio_metrics(hop);
+        self.sum_squares += rms * rms * hop.len() as f32;
+        self.samples += hop.len();
+        self.peak = self.peak.max(peak);
+        self.speech = match (self.speech, speech) {
+            (Some(a), Some(b)) => Some(a || b),
+            (a, b) => a.or(b),
+        };
+
+        if self.last_emit.elapsed() < LEVEL_INTERVAL {
+            return;
+        }
+        let _ = app.emit(
+            "audio-level",
+            AudioLevel {
+                source: self.source,
+                rms: (self.sum_squares / self.samples as f32).sqrt(),
+                peak: self.peak,
+                speech: self.speech,
+                gate_open: self.peak >= self.gate_threshold,
+            },
+        );
+        *self = Self::new(self.source, self.gate_threshold);
+    }
 }
 
 #[tauri::command]
@@ -50,20 +234,58 @@ pub async fn start_system_audio_capture(
     vad_config: Option<VadConfig>,
     device_id: Option<String>,
 ) -> Result<(), String> {
+    let events_app = app.clone();
+    start_capture(&app, CaptureSource::Them, vad_config, move || {
+        let input = SpeakerInput::new_with_device(device_id).map_err(|e| {
+            error!("Failed to create speaker input: {}", e);
+            format!("Failed to access system audio: {}", e)
+        })?;
+
+        let mut stream = input.stream();
+        if let Some(mut changes) = stream.take_device_changes() {
+            tauri::async_runtime::spawn(async move {
+                while let Some(change) = changes.recv().await {
+                    let _ = events_app.emit("audio-device-changed", change);
+                }
+            });
+        }
+        let sr = stream.sample_rate();
+        Ok((stream, sr))
+    })
+}
+
+// Shared by every capture source: opens the input through `open`, then runs the VAD (or
+// continuous) pipeline on its samples until the stream ends or the capture is stopped.
+// Each source has its own task and VAD state, so both can run at the same time.
+pub(crate) fn start_capture<S, F>(
+    app: &AppHandle,
+    source: CaptureSource,
+    vad_config: Option<VadConfig>,
+    open: F,
+) -> Result<(), String>
+where
+    S: Stream<Item = f32> + Unpin + Send + 'static,
+    F: FnOnce() -> Result<(S, u32), String>,
+{
     let state = app.state::<crate::AudioState>();
 
-    // Check if already capturing (atomic check)
-    {
+    // Check if this source is already capturing, and reserve it while the device opens
+    let _reservation = {
         let guard = state
-            .stream_task
+            .capture_tasks
+            .lock()
+            .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+        let mut starting = state
+            .starting_sources
             .lock()
             .map_err(|e| format!("Failed to acquire lock: {}", e))?;
 
-        if guard.is_some() {
-            warn!("Capture already running");
-            return Err("Capture already running".to_string());
+        if guard.contains_key(&source) || !starting.insert(source) {
+            warn!("Capture already running for {}", source.label());
+            return Err(format!("Capture already running for {}", source.label()));
         }
-    }
+        StartReservation { app, source }
+    };
 
     // Update VAD config if provided
     if let Some(config) = vad_config {
@@ -74,13 +296,7 @@ pub async fn start_system_audio_capture(
         *vad_cfg = config;
     }
 
-    let input = SpeakerInput::new_with_device(device_id).map_err(|e| {
-        error!("Failed to create speaker input: {}", e);
-        format!("Failed to access system audio: {}", e)
-    })?;
-
-    let stream = input.stream();
-    let sr = stream.sample_rate();
+    let (stream, sr) = open()?;
 
     // Validate sample rate
     if !(8000..=96000).contains(&sr) {
@@ -92,53 +308,198 @@ pub async fn start_system_audio_capture(
     }
 
     let app_clone = app.clone();
-    let vad_config = state
+    let mut vad_config = state
         .vad_config
         .lock()
         .map_err(|e| format!("Failed to read VAD config: {}", e))?
         .clone();
 
+    // Convert to the configured rate before VAD and encoding
+    let native_sr = sr;
+    let sr = vad_config.output_sample_rate.unwrap_or(native_sr);
+    if !(8000..=96000).contains(&sr) {
+        return Err(format!(
+            "Invalid output sample rate: {}. Expected 8000-96000 Hz",
+            sr
+        ));
+    }
+    let stream = ResampledStream::new(stream, native_sr, sr, vad_config.resample_quality);
+    vad_config.hop_size = rescale_hop_size(vad_config.hop_size, native_sr, sr);
+    let detector = if vad_config.enabled {
+        Some(build_detector(&vad_config, sr)?)
+    } else {
+        None
+    };
+
+    let mut tasks = state
+        .capture_tasks
+        .lock()
+        .map_err(|e| format!("Failed to acquire lock: {}", e))?;
+
+    // The first running source starts the shared timeline
+    let timeline_offset_ms = {
+        let mut timeline_start = state
+            .timeline_start
+            .lock()
+            .map_err(|e| format!("Failed to read capture timeline: {}", e))?;
+        if tasks.is_empty() || timeline_start.is_none() {
+            *timeline_start = Some(Instant::now());
+        }
+        timeline_start
+            .map(|start| start.elapsed().as_millis() as u64)
+            .unwrap_or_default()
+    };
+
     // Mark as capturing BEFORE spawning task
     *state
         .is_capturing
         .lock()
         .map_err(|e| format!("Failed to set capturing state: {}", e))? = true;
 
-    // Emit capture started event
+    // Emit capture started events; `capture-started` keeps its original sample-rate payload
     let _ = app_clone.emit("capture-started", sr);
+    let _ = app_clone.emit(
+        "capture-source-started",
+        json!({ "source": source, "sample_rate": sr, "native_sample_rate": native_sr }),
+    );
 
-    let state_clone = app.state::<crate::AudioState>();
+    let paused = Arc::new(AtomicBool::new(false));
+    state
+        .pause_flags
+        .lock()
+        .map_err(|e| format!("Failed to acquire pause lock: {}", e))?
+        .insert(source, paused.clone());
+
+    let started_at = chrono::Utc::now().timestamp_millis();
     let task = tokio::spawn(async move {
-        if vad_config.enabled {
-            run_vad_capture(app_clone.clone(), stream, sr, vad_config).await;
+        let clock = UtteranceClock {
+            source,
+            timeline_offset_ms,
+            started_at,
+            sample_rate: sr,
+        };
+        if let Some(detector) = detector {
+            run_vad_capture(
+                app_clone.clone(),
+                stream,
+                clock,
+                vad_config,
+                detector,
+                paused,
+            )
+            .await;
         } else {
-            run_continuous_capture(app_clone.clone(), stream, sr, vad_config).await;
+            run_continuous_capture(app_clone.clone(), stream, clock, vad_config, paused).await;
         }
 
+        // Reached only when the stream ended on its own (device lost, stream error);
+        // `stop_capture` aborts the task instead
         let state = app_clone.state::<crate::AudioState>();
-        {
-            if let Ok(mut guard) = state.stream_task.lock() {
-                *guard = None;
-            };
+        let remaining = state.capture_tasks.lock().ok().map(|mut guard| {
+            guard.remove(&source);
+            guard.len()
+        });
+        if let Ok(mut flags) = state.pause_flags.lock() {
+            flags.remove(&source);
+        }
+        if remaining == Some(0) {
+            mark_capture_idle(&state);
         }
+        let _ = app_clone.emit("capture-stopped", ());
+        let _ = app_clone.emit("capture-source-stopped", source);
     });
 
-    *state_clone
-        .stream_task
-        .lock()
-        .map_err(|e| format!("Failed to store task: {}", e))? = Some(task);
+    tasks.insert(source, task);
 
     Ok(())
 }
 
+// Releases a source reserved by `start_capture` once its task is registered or the
+// start failed
+struct StartReservation<'a> {
+    app: &'a AppHandle,
+    source: CaptureSource,
+}
+
+impl Drop for StartReservation<'_> {
+    fn drop(&mut self) {
+        let state = self.app.state::<crate::AudioState>();
+        if let Ok(mut starting) = state.starting_sources.lock() {
+            starting.remove(&self.source);
+        };
+    }
+}
+
+// Places a source's samples on the shared capture timeline
+#[derive(Debug, Clone, Copy)]
+struct UtteranceClock {
+    source: CaptureSource,
+    timeline_offset_ms: u64,
+    // Wall-clock Unix milliseconds of the first sample
+    started_at: i64,
+    sample_rate: u32,
+}
+
+impl UtteranceClock {
+    fn duration_ms(&self, samples: u64) -> u64 {
+        samples * 1000 / self.sample_rate as u64
+    }
+
+    fn timeline_ms(&self, sample_index: u64) -> u64 {
+        self.timeline_offset_ms + self.duration_ms(sample_index)
+    }
+
+    fn wall_clock_ms(&self, sample_index: u64) -> i64 {
+        self.started_at + self.duration_ms(sample_index) as i64
+    }
+}
+
+// Where a finished utterance starts in the source's samples, and how it was cut
+#[derive(Debug, Clone, Copy)]
+struct UtteranceSpan {
+    start: u64,
+    pre_speech: usize,
+    force_cut: bool,
+}
+
+fn build_detector(config: &VadConfig, sample_rate: u32) -> Result<Box<dyn VoiceDetector>, String> {
+    match config.detector {
+        VadKind::Energy => {
+            let vad = EnergyVad::new(config.sensitivity_rms, config.peak_threshold);
+            if config.adaptive_threshold {
+                Ok(Box::new(vad.adaptive(config.threshold_margin_db)))
+            } else {
+                Ok(Box::new(vad))
+            }
+        }
+        VadKind::Gmm => Ok(Box::new(GmmVad::new(sample_rate))),
+        #[cfg(feature = "neural-vad")]
+        VadKind::Neural => {
+            let path = config
+                .neural_model_path
+                .as_deref()
+                .ok_or("The neural VAD needs neural_model_path")?;
+            Ok(Box::new(crate::vad::NeuralVad::new(
+                std::path::Path::new(path),
+                sample_rate,
+            )?))
+        }
+        #[cfg(not(feature = "neural-vad"))]
+        VadKind::Neural => Err("This build does not include the neural VAD".to_string()),
+    }
+}
+
 // VAD-enabled capture - OPTIMIZED for real-time speech detection
 async fn run_vad_capture(
     app: AppHandle,
     stream: impl StreamExt<Item = f32> + Unpin,
-    sr: u32,
+    clock: UtteranceClock,
     config: VadConfig,
+    mut detector: Box<dyn VoiceDetector>,
+    paused: Arc<AtomicBool>,
 ) {
     let mut stream = stream;
+    let sr = clock.sample_rate;
     let mut buffer: VecDeque<f32> = VecDeque::new();
     let mut pre_speech: VecDeque<f32> =
         VecDeque::with_capacity(config.pre_speech_chunks * config.hop_size);
@@ -147,35 +508,101 @@ async fn run_vad_capture(
     let mut silence_chunks = 0;
     let mut speech_chunks = 0;
     let max_samples = sr as usize * 30; // 30s safety cap per utterance
+    let mut consumed_samples: u64 = 0;
+    let mut utterance_start: u64 = 0;
+    let mut utterance_pre_speech = 0;
+    let mut noise_floor = NoiseFloorTracker::new(sr);
+    let mut level_meter = LevelMeter::new(clock.source, config.noise_gate_threshold);
+    let mut last_floor_report: Option<Instant> = None;
 
     while let Some(sample) = stream.next().await {
         buffer.push_back(sample);
 
         // Process in fixed chunks for VAD analysis
         while buffer.len() >= config.hop_size {
-            let mut mono = Vec::with_capacity(config.hop_size);
+            let hop_start = consumed_samples;
+            consumed_samples += config.hop_size as u64;
+
+            let mut raw = Vec::with_capacity(config.hop_size);
             for _ in 0..config.hop_size {
                 if let Some(v) = buffer.pop_front() {
-                    mono.push(v);
+                    raw.push(v);
                 }
             }
 
             // Apply noise gate BEFORE VAD (critical for accuracy)
-            let mono = apply_noise_gate(&mono, config.noise_gate_threshold);
+            let mono = apply_noise_gate(&raw, config.noise_gate_threshold);
+
+            noise_floor.update(&mono);
+            if let Some(floor_rms) = noise_floor.floor_rms() {
+                detector.set_noise_floor(floor_rms);
+                if last_floor_report.is_none_or(|at| at.elapsed() >= Duration::from_secs(1)) {
+                    last_floor_report = Some(Instant::now());
+                    let _ = app.emit(
+                        "noise-floor",
+                        NoiseFloor {
+                            source: clock.source,
+                            floor_rms,
+                            floor_db: 20.0 * floor_rms.max(1e-6).log10(),
+                            threshold_rms: detector.threshold_rms(),
+                        },
+                    );
+                }
+            }
+
+            let is_speech = detector.is_speech(&mono);
+            level_meter.add(&app, &raw, Some(is_speech));
 
-            let (rms, peak) = calculate_audio_metrics(&mono);
-            let is_speech = rms > config.sensitivity_rms || peak > config.peak_threshold;
+            // While paused, detector and noise statistics stay current and the pre-speech
+            // buffer keeps rolling, so speech right after resuming is caught in full.
+            // An utterance in progress is cut at the pause rather than lost.
+            if paused.load(Ordering::Acquire) {
+                if in_speech {
+                    if speech_chunks >= config.min_speech_chunks {
+                        let span = UtteranceSpan {
+                            start: utterance_start,
+                            pre_speech: utterance_pre_speech,
+                            force_cut: true,
+                        };
+                        if deliver_utterance(&app, clock, span, &speech_buffer).is_err() {
+                            error!("Failed to encode speech to WAV");
+                            let _ = app.emit("audio-encoding-error", "Failed to encode speech");
+                        }
+                    } else {
+                        discard_utterance(
+                            &app,
+                            clock,
+                            DiscardReason::TooShort,
+                            utterance_start,
+                            speech_buffer.len(),
+                            speech_chunks * config.hop_size,
+                        );
+                    }
+                    speech_buffer.clear();
+                    in_speech = false;
+                    silence_chunks = 0;
+                    speech_chunks = 0;
+                }
+                pre_speech.extend(mono);
+                let excess = pre_speech
+                    .len()
+                    .saturating_sub(config.pre_speech_chunks * config.hop_size);
+                pre_speech.drain(..excess);
+                continue;
+            }
 
             if is_speech {
                 if !in_speech {
                     // Speech START detected
                     in_speech = true;
                     speech_chunks = 0;
+                    utterance_pre_speech = pre_speech.len();
+                    utterance_start = hop_start.saturating_sub(utterance_pre_speech as u64);
 
                     // Include pre-speech buffer for natural sound
                     speech_buffer.extend(pre_speech.drain(..));
 
-                    let _ = app.emit("speech-start", ());
+                    let _ = app.emit("speech-start", clock.source);
                 }
 
                 speech_chunks += 1;
@@ -184,11 +611,12 @@ async fn run_vad_capture(
 
                 // Safety cap: force emit if exceeds 30s
                 if speech_buffer.len() > max_samples {
-                    let normalized_buffer = normalize_audio_level(&speech_buffer, 0.1);
-                    if let Ok(b64) = samples_to_wav_b64(sr, &normalized_buffer) {
-                        // let duration = speech_buffer.len() as f32 / sr as f32;
-                        let _ = app.emit("speech-detected", b64);
-                    }
+                    let span = UtteranceSpan {
+                        start: utterance_start,
+                        pre_speech: utterance_pre_speech,
+                        force_cut: true,
+                    };
+                    let _ = deliver_utterance(&app, clock, span, &speech_buffer);
                     speech_buffer.clear();
                     in_speech = false;
                     speech_chunks = 0;
@@ -216,18 +644,24 @@ async fn run_vad_capture(
                             }
 
                             // Emit complete speech segment
-                            let normalized_buffer = normalize_audio_level(&speech_buffer, 0.1);
-                            if let Ok(b64) = samples_to_wav_b64(sr, &normalized_buffer) {
-                                // let duration = speech_buffer.len() as f32 / sr as f32;
-                                let _ = app.emit("speech-detected", b64);
-                            } else {
+                            let span = UtteranceSpan {
+                                start: utterance_start,
+                                pre_speech: utterance_pre_speech,
+                                force_cut: false,
+                            };
+                            if deliver_utterance(&app, clock, span, &speech_buffer).is_err() {
                                 error!("Failed to encode speech to WAV");
                                 let _ = app.emit("audio-encoding-error", "Failed to encode speech");
                             }
                         } else {
-                            let _ = app.emit(
-                                "speech-discarded",
-                                "Audio too short (likely background noise)",
+                            // Likely background noise
+                            discard_utterance(
+                                &app,
+                                clock,
+                                DiscardReason::TooShort,
+                                utterance_start,
+                                speech_buffer.len(),
+                                speech_chunks * config.hop_size,
                             );
                         }
 
@@ -254,16 +688,49 @@ async fn run_vad_capture(
             }
         }
     }
+
+    if in_speech {
+        discard_utterance(
+            &app,
+            clock,
+            DiscardReason::StreamEnded,
+            utterance_start,
+            speech_buffer.len(),
+            speech_chunks * config.hop_size,
+        );
+    }
+}
+
+fn discard_utterance(
+    app: &AppHandle,
+    clock: UtteranceClock,
+    reason: DiscardReason,
+    start: u64,
+    samples: usize,
+    speech_samples: usize,
+) {
+    let _ = app.emit(
+        "speech-discarded",
+        SpeechDiscarded {
+            source: clock.source,
+            reason,
+            timeline_ms: clock.timeline_ms(start),
+            duration_ms: clock.duration_ms(samples as u64),
+            speech_ms: clock.duration_ms(speech_samples as u64),
+        },
+    );
 }
 
 // Continuous capture (VAD disabled)
 async fn run_continuous_capture(
     app: AppHandle,
     stream: impl StreamExt<Item = f32> + Unpin,
-    sr: u32,
+    clock: UtteranceClock,
     config: VadConfig,
+    paused: Arc<AtomicBool>,
 ) {
     let mut stream = stream;
+    let sr = clock.sample_rate;
     let max_samples = (sr as u64 * config.max_recording_duration_secs) as usize;
 
     // Pre-allocate buffer to prevent reallocations
@@ -286,6 +753,14 @@ async fn run_continuous_capture(
         config.max_recording_duration_secs,
     );
 
+    // Set when a size or time limit ended the recording rather than a manual stop
+    let mut force_cut = false;
+    let mut level_meter = LevelMeter::new(clock.source, config.noise_gate_threshold);
+    let mut level_hop = Vec::with_capacity(config.hop_size);
+    // Paused time doesn't count toward the duration limit
+    let mut paused_since: Option<Instant> = None;
+    let mut paused_total = Duration::ZERO;
+
     // Accumulate audio - check stop flag on EVERY sample for immediate response
     loop {
         // Check stop flag FIRST on every iteration for immediate stopping
@@ -301,9 +776,24 @@ async fn run_continuous_capture(
                             break;
                         }
 
+                        level_hop.push(sample);
+                        if level_hop.len() >= config.hop_size {
+                            level_meter.add(&app, &level_hop, None);
+                            level_hop.clear();
+                        }
+
+                        // Samples arriving while paused are dropped
+                        if paused.load(Ordering::Acquire) {
+                            paused_since.get_or_insert_with(Instant::now);
+                            continue;
+                        }
+                        if let Some(since) = paused_since.take() {
+                            paused_total += since.elapsed();
+                        }
+
                         audio_buffer.push(sample);
 
-                        let elapsed = start_time.elapsed();
+                        let elapsed = start_time.elapsed().saturating_sub(paused_total);
 
                         // Emit progress every second
                         if audio_buffer.len() % (sr as usize) == 0 {
@@ -312,11 +802,13 @@ async fn run_continuous_capture(
 
                         // Check size limit (safety)
                         if audio_buffer.len() >= max_samples {
+                            force_cut = true;
                             break;
                         }
 
                         // Check time limit
                         if elapsed >= max_duration {
+                            force_cut = true;
                             break;
                         }
                     },
@@ -340,16 +832,15 @@ async fn run_continuous_capture(
 
         // Apply noise gate
         let cleaned_audio = apply_noise_gate(&audio_buffer, config.noise_gate_threshold);
-        let cleaned_audio = normalize_audio_level(&cleaned_audio, 0.1);
-
-        match samples_to_wav_b64(sr, &cleaned_audio) {
-            Ok(b64) => {
-                let _ = app.emit("speech-detected", b64);
-            }
-            Err(e) => {
-                error!("Failed to encode continuous audio: {}", e);
-                let _ = app.emit("audio-encoding-error", e);
-            }
+        let span = UtteranceSpan {
+            start: 0,
+            pre_speech: 0,
+            force_cut,
+        };
+
+        if let Err(e) = deliver_utterance(&app, clock, span, &cleaned_audio) {
+            error!("Failed to encode continuous audio: {}", e);
+            let _ = app.emit("audio-encoding-error", e);
         }
     } else {
         warn!("No audio captured in continuous mode");
@@ -359,38 +850,6 @@ async fn run_continuous_capture(
     let _ = app.emit("continuous-recording-stopped", ());
 }
 
-// Apply noise gate
-fn apply_noise_gate(samples: &[f32], threshold: f32) -> Vec<f32> {
-    const KNEE_RATIO: f32 = 3.0; // Compression ratio for soft knee
-
-    samples
-        .iter()
-        .map(|&s| {
-            let abs = s.abs();
-            if abs < threshold {
-                s * (abs / threshold).powf(1.0 / KNEE_RATIO)
-            } else {
-                s
-            }
-        })
-        .collect()
-}
-
-// Calculate RMS and peak (optimized)
-fn calculate_audio_metrics(chunk: &[f32]) -> (f32, f32) {
-    let mut sumsq = 0.0f32;
-    let mut peak = 0.0f32;
-
-    for &v in chunk {
-        let a = v.abs();
-        peak = peak.max(a);
-        sumsq += v * v;
-    }
-
-    let rms = (sumsq / chunk.len() as f32).sqrt();
-    (rms, peak)
-}
-
 fn normalize_audio_level(samples: &[f32], target_rms: f32) -> Vec<f32> {
     if samples.is_empty() {
         return Vec::new();
@@ -418,8 +877,55 @@ fn normalize_audio_level(samples: &[f32], target_rms: f32) -> Vec<f32> {
         .collect()
 }
 
-// Convert samples to WAV base64 (with proper error handling)
-fn samples_to_wav_b64(sample_rate: u32, mono_f32: &[f32]) -> Result<String, String> {
+// Normalizes a finished utterance and sends it to the backend pipeline when it is enabled,
+// otherwise to the webview as base64 WAV with its metadata on `speech-detected`
+fn deliver_utterance(
+    app: &AppHandle,
+    clock: UtteranceClock,
+    span: UtteranceSpan,
+    mono_f32: &[f32],
+) -> Result<(), String> {
+    let (rms, peak) = audio_metrics(mono_f32);
+    let end = span.start + mono_f32.len() as u64;
+    let metadata = UtteranceMetadata {
+        id: uuid::Uuid::new_v4().to_string(),
+        source: clock.source,
+        timeline_ms: clock.timeline_ms(span.start),
+        started_at: clock.wall_clock_ms(span.start),
+        ended_at: clock.wall_clock_ms(end),
+        duration_ms: clock.duration_ms(mono_f32.len() as u64),
+        sample_rate: clock.sample_rate,
+        peak,
+        rms,
+        force_cut: span.force_cut,
+        pre_speech_ms: clock.duration_ms(span.pre_speech as u64),
+    };
+
+    let normalized = normalize_audio_level(mono_f32, 0.1);
+    let wav_bytes = samples_to_wav_bytes(clock.sample_rate, &normalized)?;
+
+    if crate::pipeline::load_pipeline_config(app).enabled {
+        let utterance = UtteranceInfo {
+            id: metadata.id,
+            source: metadata.source,
+            timeline_ms: metadata.timeline_ms,
+            duration_ms: metadata.duration_ms,
+        };
+        crate::pipeline::submit_utterance(app, wav_bytes, utterance);
+    } else {
+        let _ = app.emit(
+            "speech-detected",
+            SpeechDetected {
+                audio: B64.encode(wav_bytes),
+                metadata,
+            },
+        );
+    }
+    Ok(())
+}
+
+// Convert samples to 16-bit mono WAV bytes (with proper error handling)
+fn samples_to_wav_bytes(sample_rate: u32, mono_f32: &[f32]) -> Result<Vec<u8>, String> {
     // Validate sample rate
     if !(8000..=96000).contains(&sample_rate) {
         error!("Invalid sample rate: {}", sample_rate);
@@ -455,39 +961,112 @@ fn samples_to_wav_b64(sample_rate: u32, mono_f32: &[f32]) -> Result<String, Stri
 
     writer.finalize().map_err(|e| e.to_string())?;
 
-    Ok(B64.encode(cursor.into_inner()))
+    Ok(cursor.into_inner())
 }
 
 #[tauri::command]
 pub async fn stop_system_audio_capture(app: AppHandle) -> Result<(), String> {
+    stop_capture(&app, CaptureSource::Them).await
+}
+
+// Stops one source; other running sources keep capturing
+pub(crate) async fn stop_capture(app: &AppHandle, source: CaptureSource) -> Result<(), String> {
     let state = app.state::<crate::AudioState>();
 
     // Abort task in separate scope (Send trait fix)
-    {
+    let remaining = {
         let mut guard = state
-            .stream_task
+            .capture_tasks
             .lock()
             .map_err(|e| format!("Failed to acquire task lock: {}", e))?;
 
-        if let Some(task) = guard.take() {
+        if let Some(task) = guard.remove(&source) {
             task.abort();
         }
+        guard.len()
+    };
+    if let Ok(mut flags) = state.pause_flags.lock() {
+        flags.remove(&source);
     }
 
     // LONGER delay for proper cleanup (300ms instead of 150ms)
     tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
 
-    // Mark as not capturing
-    *state
-        .is_capturing
-        .lock()
-        .map_err(|e| format!("Failed to update capturing state: {}", e))? = false;
+    // Mark as not capturing once the last source stopped
+    if remaining == 0 {
+        mark_capture_idle(&state);
+    }
 
     // Additional cleanup delay (CRITICAL for mic indicator)
     tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
 
-    // Emit stopped event
+    // Emit stopped events; `capture-stopped` keeps its original empty payload
     let _ = app.emit("capture-stopped", ());
+    let _ = app.emit("capture-source-stopped", source);
+    Ok(())
+}
+
+// Same hop duration at the output rate as `hop_size` samples at the device rate
+fn rescale_hop_size(hop_size: usize, native_sr: u32, output_sr: u32) -> usize {
+    ((hop_size as u64 * output_sr as u64 + native_sr as u64 / 2) / native_sr as u64).max(1) as usize
+}
+
+fn mark_capture_idle(state: &crate::AudioState) {
+    if let Ok(mut is_capturing) = state.is_capturing.lock() {
+        *is_capturing = false;
+    }
+    if let Ok(mut timeline_start) = state.timeline_start.lock() {
+        *timeline_start = None;
+    }
+}
+
+// Stops producing utterances from a running source (every source when `None`) while
+// keeping its device, buffers and VAD statistics, so resuming is immediate
+#[tauri::command]
+pub async fn pause_capture(app: AppHandle, source: Option<CaptureSource>) -> Result<(), String> {
+    set_paused(&app, source, true)
+}
+
+#[tauri::command]
+pub async fn resume_capture(app: AppHandle, source: Option<CaptureSource>) -> Result<(), String> {
+    set_paused(&app, source, false)
+}
+
+fn set_paused(app: &AppHandle, source: Option<CaptureSource>, paused: bool) -> Result<(), String> {
+    let state = app.state::<crate::AudioState>();
+    let flags = state
+        .pause_flags
+        .lock()
+        .map_err(|e| format!("Failed to acquire pause lock: {}", e))?;
+
+    let targets: Vec<_> = match source {
+        Some(source) => {
+            let flag = flags
+                .get(&source)
+                .ok_or_else(|| format!("No capture running for {}", source.label()))?;
+            vec![(source, flag.clone())]
+        }
+        None => flags
+            .iter()
+            .map(|(source, flag)| (*source, flag.clone()))
+            .collect(),
+    };
+    if targets.is_empty() {
+        return Err("No capture running".to_string());
+    }
+    drop(flags);
+
+    let event = if paused {
+        "capture-paused"
+    } else {
+        "capture-resumed"
+    };
+    for (source, flag) in targets {
+        // Only report sources whose state actually changed
+        if flag.swap(paused, Ordering::AcqRel) != paused {
+            let _ = app.emit(event, source);
+        }
+    }
     Ok(())
 }
 
@@ -573,9 +1152,17 @@ pub async fn update_vad_config(app: AppHandle, config: VadConfig) -> Result<(),
     if config.sensitivity_rms < 0.0 || config.sensitivity_rms > 1.0 {
         return Err("Invalid sensitivity_rms: must be 0.0-1.0".to_string());
     }
+    if !(0.0..=40.0).contains(&config.threshold_margin_db) {
+        return Err("Invalid threshold_margin_db: must be 0-40 dB".to_string());
+    }
     if config.max_recording_duration_secs > 3600 {
         return Err("Invalid max_recording_duration_secs: must be <= 3600 (1 hour)".to_string());
     }
+    if let Some(rate) = config.output_sample_rate {
+        if !(8000..=96000).contains(&rate) {
+            return Err("Invalid output_sample_rate: must be 8000-96000 Hz".to_string());
+        }
+    }
 
     let state = app.state::<crate::AudioState>();
     *state
@@ -586,6 +1173,26 @@ pub async fn update_vad_config(app: AppHandle, config: VadConfig) -> Result<(),
     Ok(())
 }
 
+// Microphones and output monitors, each with the ID its start command expects. A backend
+// that fails to list (e.g. no PulseAudio server) is skipped so the other still shows up.
+#[tauri::command]
+pub async fn list_audio_devices() -> Result<Vec<AudioDevice>, String> {
+    tokio::task::spawn_blocking(|| {
+        let mut devices = Vec::new();
+        match crate::audio::list_input_devices() {
+            Ok(inputs) => devices.extend(inputs),
+            Err(e) => warn!("Failed to list input devices: {}", e),
+        }
+        match list_output_devices() {
+            Ok(outputs) => devices.extend(outputs),
+            Err(e) => warn!("Failed to list output devices: {}", e),
+        }
+        devices
+    })
+    .await
+    .map_err(|e| format!("Failed to list audio devices: {}", e))
+}
+
 #[tauri::command]
 pub async fn get_capture_status(app: AppHandle) -> Result<bool, String> {
     let state = app.state::<crate::AudioState>();
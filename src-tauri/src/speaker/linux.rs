@@ -1,19 +1,44 @@
-// Pluely linux speaker input and stream
+// Pluely linux speaker input and stream, on the asynchronous PulseAudio API (served by
+// PipeWire's pulse server too). Without a device ID the capture follows the default output;
+// it reconnects after the device or server goes away and reports both as `DeviceChange`s.
+//
+// The ignored tests below drive it against null sinks on a running server, which works
+// headless: `cargo test speaker::linux -- --ignored --test-threads=1`.
+use super::{AudioDevice, AudioDeviceKind, DeviceChange, DeviceChangeReason};
 use anyhow::{anyhow, Result};
 use futures_util::Stream;
+use std::cell::{Cell, RefCell};
 use std::collections::VecDeque;
+use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 use std::task::{Poll, Waker};
 use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 
 use libpulse_binding as pulse;
-use libpulse_simple_binding as psimple;
 
-use psimple::Simple;
+use pulse::callbacks::ListResult;
+use pulse::context::subscribe::{Facility, InterestMaskSet};
+use pulse::context::{Context, FlagSet as ContextFlagSet, State as ContextState};
+use pulse::def::BufferAttr;
+use pulse::mainloop::standard::Mainloop;
+use pulse::operation::{Operation, State as OperationState};
 use pulse::sample::{Format, Spec};
-use pulse::stream::Direction;
+use pulse::stream::{FlagSet as StreamFlagSet, PeekResult, State as StreamState};
+use pulse::time::MicroSeconds;
 
+// Fixed across reconnects so timestamps downstream stay valid; matches macOS/Windows
 const DEFAULT_SAMPLE_RATE: u32 = 44_100;
+const FOLLOW_DEFAULT_MONITOR: &str = "@DEFAULT_MONITOR@";
+const FRAGMENT_BYTES: u32 = 4096; // 1024 f32 samples per read
+const MAX_BUFFER_SIZE: usize = 131072; // matching macOS/Windows
+
+// The mainloop never blocks longer than this, so shutdown is noticed promptly
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const RECONNECT_DELAY_MIN: Duration = Duration::from_millis(500);
+const RECONNECT_DELAY_MAX: Duration = Duration::from_secs(5);
 
 pub struct SpeakerInput {
     source_name: Option<String>,
@@ -35,20 +60,20 @@ impl SpeakerInput {
             shutdown: false,
         }));
         let (init_tx, init_rx) = std::sync::mpsc::channel();
+        let (change_tx, change_rx) = unbounded_channel();
 
         let queue_clone = sample_queue.clone();
         let waker_clone = waker_state.clone();
         let source_name = self.source_name;
 
         let mut capture_thread = Some(thread::spawn(move || {
-            if let Err(e) = SpeakerStream::capture_audio_loop(
+            SpeakerStream::capture_audio_loop(
                 queue_clone,
                 waker_clone,
                 source_name.as_deref(),
                 init_tx,
-            ) {
-                eprintln!("Audio capture loop failed: {}", e);
-            }
+                change_tx,
+            )
         }));
 
         let (sample_rate, init_success) = match init_rx.recv() {
@@ -83,6 +108,7 @@ impl SpeakerInput {
             waker_state,
             capture_thread,
             sample_rate,
+            device_changes: Some(change_rx),
         }
     }
 }
@@ -93,11 +119,16 @@ struct WakerState {
     shutdown: bool,
 }
 
+fn is_shutdown(waker_state: &Mutex<WakerState>) -> bool {
+    waker_state.lock().unwrap().shutdown
+}
+
 pub struct SpeakerStream {
     sample_queue: Arc<Mutex<VecDeque<f32>>>,
     waker_state: Arc<Mutex<WakerState>>,
     capture_thread: Option<thread::JoinHandle<()>>,
     sample_rate: u32,
+    device_changes: Option<UnboundedReceiver<DeviceChange>>,
 }
 
 impl SpeakerStream {
@@ -105,121 +136,411 @@ impl SpeakerStream {
         self.sample_rate
     }
 
+    pub fn take_device_changes(&mut self) -> Option<UnboundedReceiver<DeviceChange>> {
+        self.device_changes.take()
+    }
+
+    // Keeps a recording session open until shutdown. Only the first connection reports
+    // errors through `init_tx`; later failures are retried with backoff.
     fn capture_audio_loop(
         sample_queue: Arc<Mutex<VecDeque<f32>>>,
         waker_state: Arc<Mutex<WakerState>>,
         source_name: Option<&str>,
         init_tx: std::sync::mpsc::Sender<Result<u32>>,
-    ) -> Result<()> {
+        changes: UnboundedSender<DeviceChange>,
+    ) {
+        let follow_default = matches!(source_name, None | Some(FOLLOW_DEFAULT_MONITOR));
+        let mut init_tx = Some(init_tx);
+        let mut reconnect_delay = RECONNECT_DELAY_MIN;
+        let mut pending_reason = DeviceChangeReason::Reconnected;
+
+        while !is_shutdown(&waker_state) {
+            let session = match CaptureSession::open(source_name, follow_default) {
+                Ok(session) => session,
+                Err(e) => {
+                    if let Some(init_tx) = init_tx.take() {
+                        let _ = init_tx.send(Err(e));
+                        return;
+                    }
+                    eprintln!("PulseAudio reconnect failed: {}", e);
+                    sleep_unless_shutdown(&waker_state, reconnect_delay);
+                    reconnect_delay = (reconnect_delay * 2).min(RECONNECT_DELAY_MAX);
+                    continue;
+                }
+            };
+
+            match init_tx.take() {
+                Some(init_tx) => {
+                    let _ = init_tx.send(Ok(DEFAULT_SAMPLE_RATE));
+                }
+                None => {
+                    let _ = changes.send(DeviceChange {
+                        device_id: session.device_id.clone(),
+                        reason: pending_reason,
+                    });
+                }
+            }
+            reconnect_delay = RECONNECT_DELAY_MIN;
+
+            let mut session = session;
+            match session.run(&sample_queue, &waker_state) {
+                SessionEnd::Shutdown => break,
+                SessionEnd::DefaultChanged => {
+                    pending_reason = DeviceChangeReason::DefaultChanged;
+                }
+                SessionEnd::Lost(e) => {
+                    eprintln!("PulseAudio capture lost: {}", e);
+                    let _ = changes.send(DeviceChange {
+                        device_id: session.device_id.clone(),
+                        reason: DeviceChangeReason::Lost,
+                    });
+                    pending_reason = DeviceChangeReason::Reconnected;
+                }
+            }
+        }
+    }
+}
+
+fn sleep_unless_shutdown(waker_state: &Mutex<WakerState>, duration: Duration) {
+    let deadline = Instant::now() + duration;
+    while Instant::now() < deadline && !is_shutdown(waker_state) {
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn push_samples(
+    sample_queue: &Mutex<VecDeque<f32>>,
+    waker_state: &Mutex<WakerState>,
+    samples: &[f32],
+) {
+    // Consistent buffer overflow handling
+    let dropped = {
+        let mut queue = sample_queue.lock().unwrap();
+        queue.extend(samples.iter());
+
+        // If buffer exceeds maximum, drop oldest samples
+        if queue.len() > MAX_BUFFER_SIZE {
+            let to_drop = queue.len() - MAX_BUFFER_SIZE;
+            queue.drain(0..to_drop);
+            to_drop
+        } else {
+            0
+        }
+    };
+
+    if dropped > 0 {
+        eprintln!("Linux buffer overflow - dropped {} samples", dropped);
+    }
+
+    // Wake up consumer
+    let mut state = waker_state.lock().unwrap();
+    if !state.has_data {
+        state.has_data = true;
+        if let Some(waker) = state.waker.take() {
+            drop(state);
+            waker.wake();
+        }
+    }
+}
+
+enum SessionEnd {
+    Shutdown,
+    DefaultChanged,
+    Lost(anyhow::Error),
+}
+
+type ServerInfoQuery = (
+    Operation<dyn FnMut(&pulse::context::introspect::ServerInfo)>,
+    Rc<RefCell<Option<String>>>,
+);
+
+// One connection to the server recording one source. Fields drop in declaration order,
+// so the stream goes before its context and the context before its mainloop.
+struct CaptureSession {
+    stream: pulse::stream::Stream,
+    context: Context,
+    mainloop: Mainloop,
+    device_id: String,
+    follow_default: bool,
+    server_changed: Rc<Cell<bool>>,
+}
+
+impl CaptureSession {
+    fn open(source_name: Option<&str>, follow_default: bool) -> Result<Self> {
         let spec = Spec {
             format: Format::F32le,
             channels: 1,
-            rate: 44100, // Fixed: Use 44100 Hz to match macOS/Windows
+            rate: DEFAULT_SAMPLE_RATE,
         };
-
         if !spec.is_valid() {
             return Err(anyhow!("Invalid audio specification"));
         }
 
-        let source_name = source_name
-            .map(|s| s.to_string())
-            .or_else(get_default_monitor_source);
-
-        let init_result: Result<(Simple, u32)> = (|| {
-            let simple = Simple::new(
-                None,                   // Use default server
-                "pluely",               // Application name
-                Direction::Record,      // Record direction
-                source_name.as_deref(), // Source name (monitor)
-                "System Audio Capture", // Stream description
-                &spec,                  // Sample specification
-                None,                   // Channel map (use default)
-                None,                   // Buffer attributes (use default)
-            )
-            .map_err(|e| anyhow!("Failed to create PulseAudio simple connection: {}", e))?;
+        let mut mainloop =
+            Mainloop::new().ok_or_else(|| anyhow!("Failed to create PulseAudio mainloop"))?;
+        let mut context = connect_context(&mut mainloop)?;
+
+        // Resolve the default ourselves so a later switch can be told apart from the
+        // server moving the stream
+        let device_id = if follow_default {
+            let (op, default_sink) = query_default_sink(&context);
+            wait_for_operation(&mut mainloop, op)?;
+            let default_sink = default_sink
+                .borrow()
+                .clone()
+                .ok_or_else(|| anyhow!("No default output device"))?;
+            format!("{}.monitor", default_sink)
+        } else {
+            source_name.unwrap_or_default().to_string()
+        };
 
-            Ok((simple, spec.rate))
-        })();
+        let mut stream =
+            pulse::stream::Stream::new(&mut context, "System Audio Capture", &spec, None)
+                .ok_or_else(|| anyhow!("Failed to create PulseAudio stream"))?;
+        let buffer_attr = BufferAttr {
+            maxlength: u32::MAX,
+            tlength: u32::MAX,
+            prebuf: u32::MAX,
+            minreq: u32::MAX,
+            fragsize: FRAGMENT_BYTES,
+        };
+        // A chosen device must not be swapped out behind our back: when it goes away,
+        // module-rescue-streams would move the stream to the default source (usually the
+        // microphone). With DONT_MOVE the stream fails instead and is reported as lost.
+        let mut flags = StreamFlagSet::ADJUST_LATENCY;
+        if !follow_default {
+            flags |= StreamFlagSet::DONT_MOVE;
+        }
+        stream
+            .connect_record(Some(&device_id), Some(&buffer_attr), flags)
+            .map_err(|e| anyhow!("Failed to record from {}: {}", device_id, e))?;
+
+        let deadline = Instant::now() + CONNECT_TIMEOUT;
+        loop {
+            match stream.get_state() {
+                StreamState::Ready => break,
+                StreamState::Failed | StreamState::Terminated => {
+                    return Err(anyhow!("Failed to record from {}", device_id));
+                }
+                _ => {}
+            }
+            if Instant::now() > deadline {
+                return Err(anyhow!("Timed out opening {}", device_id));
+            }
+            iterate(&mut mainloop)?;
+        }
 
-        match init_result {
-            Ok((simple, sample_rate)) => {
-                let _ = init_tx.send(Ok(sample_rate));
+        let server_changed = Rc::new(Cell::new(false));
+        if follow_default {
+            let flag = server_changed.clone();
+            context.set_subscribe_callback(Some(Box::new(move |facility, _, _| {
+                if facility == Some(Facility::Server) {
+                    flag.set(true);
+                }
+            })));
+            context.subscribe(InterestMaskSet::SERVER, |_| {});
+        }
 
-                // Buffer for reading audio data
-                let mut buffer = vec![0u8; 4096]; // 1024 f32 samples * 4 bytes each
+        Ok(Self {
+            stream,
+            context,
+            mainloop,
+            device_id,
+            follow_default,
+            server_changed,
+        })
+    }
 
-                loop {
-                    if waker_state.lock().unwrap().shutdown {
-                        break;
-                    }
+    fn run(
+        &mut self,
+        sample_queue: &Mutex<VecDeque<f32>>,
+        waker_state: &Mutex<WakerState>,
+    ) -> SessionEnd {
+        let mut default_query: Option<ServerInfoQuery> = None;
 
-                    match simple.read(&mut buffer) {
-                        Ok(_) => {
-                            // Convert byte buffer to f32 samples
-                            let samples: Vec<f32> = buffer
-                                .chunks_exact(4)
-                                .map(|chunk| {
-                                    f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])
-                                })
-                                .collect();
-
-                            if !samples.is_empty() {
-                                // Consistent buffer overflow handling
-                                let dropped = {
-                                    let mut queue = sample_queue.lock().unwrap();
-                                    let max_buffer_size = 131072; // 128KB buffer (matching macOS/Windows)
-
-                                    queue.extend(samples.iter());
-
-                                    // If buffer exceeds maximum, drop oldest samples
-                                    let dropped_count = if queue.len() > max_buffer_size {
-                                        let to_drop = queue.len() - max_buffer_size;
-                                        queue.drain(0..to_drop);
-                                        to_drop
-                                    } else {
-                                        0
-                                    };
-
-                                    dropped_count
-                                };
-
-                                if dropped > 0 {
-                                    eprintln!(
-                                        "Linux buffer overflow - dropped {} samples",
-                                        dropped
-                                    );
-                                }
-
-                                // Wake up consumer
-                                {
-                                    let mut state = waker_state.lock().unwrap();
-                                    if !state.has_data {
-                                        state.has_data = true;
-                                        if let Some(waker) = state.waker.take() {
-                                            drop(state);
-                                            waker.wake();
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("PulseAudio read error: {}", e);
-                            thread::sleep(std::time::Duration::from_millis(100));
-                        }
+        loop {
+            if is_shutdown(waker_state) {
+                return SessionEnd::Shutdown;
+            }
+            if let Err(e) = iterate(&mut self.mainloop) {
+                return SessionEnd::Lost(e);
+            }
+            match self.context.get_state() {
+                ContextState::Ready => {}
+                state => {
+                    return SessionEnd::Lost(anyhow!("PulseAudio connection {:?}", state));
+                }
+            }
+            match self.stream.get_state() {
+                StreamState::Ready => {}
+                state => return SessionEnd::Lost(anyhow!("Capture stream {:?}", state)),
+            }
+            if let Err(e) = self.read_available(sample_queue, waker_state) {
+                return SessionEnd::Lost(e);
+            }
+
+            // Server events include default device switches; check which sink is default now
+            if self.server_changed.replace(false) && default_query.is_none() {
+                default_query = Some(query_default_sink(&self.context));
+            }
+            let finished = default_query
+                .as_ref()
+                .is_some_and(|(op, _)| op.get_state() != OperationState::Running);
+            if finished {
+                let (_, default_sink) = default_query.take().unwrap();
+                let default_sink = default_sink.borrow().clone();
+                if let Some(sink) = default_sink {
+                    if self.follow_default && format!("{}.monitor", sink) != self.device_id {
+                        return SessionEnd::DefaultChanged;
                     }
                 }
             }
-            Err(e) => {
-                let _ = init_tx.send(Err(e));
+        }
+    }
+
+    fn read_available(
+        &mut self,
+        sample_queue: &Mutex<VecDeque<f32>>,
+        waker_state: &Mutex<WakerState>,
+    ) -> Result<()> {
+        let mut samples = Vec::new();
+        loop {
+            match self
+                .stream
+                .peek()
+                .map_err(|e| anyhow!("PulseAudio read error: {}", e))?
+            {
+                PeekResult::Empty => break,
+                PeekResult::Hole(_) => {}
+                PeekResult::Data(bytes) => {
+                    // Convert byte buffer to f32 samples
+                    samples.extend(
+                        bytes.chunks_exact(4).map(|chunk| {
+                            f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])
+                        }),
+                    );
+                }
             }
+            self.stream
+                .discard()
+                .map_err(|e| anyhow!("PulseAudio read error: {}", e))?;
+        }
+
+        if !samples.is_empty() {
+            push_samples(sample_queue, waker_state, &samples);
         }
         Ok(())
     }
 }
 
-fn get_default_monitor_source() -> Option<String> {
-    Some("@DEFAULT_MONITOR@".to_string())
+impl Drop for CaptureSession {
+    fn drop(&mut self) {
+        let _ = self.stream.disconnect();
+        self.context.disconnect();
+    }
+}
+
+// Runs one mainloop iteration, waiting at most `POLL_INTERVAL` for events
+fn iterate(mainloop: &mut Mainloop) -> Result<()> {
+    let timeout = MicroSeconds(POLL_INTERVAL.as_micros() as u64);
+    mainloop
+        .prepare(Some(timeout))
+        .and_then(|_| mainloop.poll())
+        .and_then(|_| mainloop.dispatch())
+        .map(|_| ())
+        .map_err(|e| anyhow!("PulseAudio mainloop failed: {}", e))
+}
+
+fn connect_context(mainloop: &mut Mainloop) -> Result<Context> {
+    let mut context = Context::new(mainloop, "pluely")
+        .ok_or_else(|| anyhow!("Failed to create PulseAudio context"))?;
+    context
+        .connect(None, ContextFlagSet::NOFLAGS, None)
+        .map_err(|e| anyhow!("Failed to connect to PulseAudio: {}", e))?;
+
+    let deadline = Instant::now() + CONNECT_TIMEOUT;
+    loop {
+        match context.get_state() {
+            ContextState::Ready => return Ok(context),
+            ContextState::Failed | ContextState::Terminated => {
+                return Err(anyhow!("PulseAudio connection failed"));
+            }
+            _ => {}
+        }
+        if Instant::now() > deadline {
+            return Err(anyhow!("Timed out connecting to PulseAudio"));
+        }
+        iterate(mainloop)?;
+    }
+}
+
+fn query_default_sink(context: &Context) -> ServerInfoQuery {
+    let default_sink = Rc::new(RefCell::new(None));
+    let default_sink_clone = default_sink.clone();
+    let op = context.introspect().get_server_info(move |info| {
+        *default_sink_clone.borrow_mut() = info.default_sink_name.as_ref().map(|n| n.to_string());
+    });
+    (op, default_sink)
+}
+
+// Runs the mainloop until `op` completes
+fn wait_for_operation<G: ?Sized>(mainloop: &mut Mainloop, mut op: Operation<G>) -> Result<()> {
+    let deadline = Instant::now() + CONNECT_TIMEOUT;
+    while op.get_state() == OperationState::Running {
+        if Instant::now() > deadline {
+            op.cancel();
+            return Err(anyhow!("Timed out waiting for PulseAudio"));
+        }
+        iterate(mainloop)?;
+    }
+    Ok(())
+}
+
+// Monitor sources of every sink. IDs are PulseAudio source names, which is what
+// `SpeakerInput::new` expects.
+pub fn list_output_devices() -> Result<Vec<AudioDevice>> {
+    let mut mainloop =
+        Mainloop::new().ok_or_else(|| anyhow!("Failed to create PulseAudio mainloop"))?;
+    let mut context = connect_context(&mut mainloop)?;
+
+    let introspector = context.introspect();
+
+    let (op, default_sink) = query_default_sink(&context);
+    wait_for_operation(&mut mainloop, op)?;
+
+    let devices = Rc::new(RefCell::new(Vec::new()));
+    let devices_clone = devices.clone();
+    let default_sink_name = default_sink.borrow().clone();
+    let op = introspector.get_source_info_list(move |result| {
+        let ListResult::Item(info) = result else {
+            return;
+        };
+        if info.monitor_of_sink.is_none() {
+            return;
+        }
+        let Some(name) = info.name.as_ref().map(|n| n.to_string()) else {
+            return;
+        };
+
+        devices_clone.borrow_mut().push(AudioDevice {
+            name: info
+                .description
+                .as_ref()
+                .map(|d| d.to_string())
+                .unwrap_or_else(|| name.clone()),
+            is_default: info.monitor_of_sink_name.is_some()
+                && info.monitor_of_sink_name.as_deref() == default_sink_name.as_deref(),
+            id: name,
+            kind: AudioDeviceKind::OutputMonitor,
+            channels: info.sample_spec.channels as u16,
+            sample_rate: info.sample_spec.rate,
+        });
+    });
+    wait_for_operation(&mut mainloop, op)?;
+
+    context.disconnect();
+    let devices = devices.borrow().clone();
+    Ok(devices)
 }
 
 impl Drop for SpeakerStream {
@@ -259,3 +580,121 @@ impl Stream for SpeakerStream {
         Poll::Pending
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    const CHANGE_TIMEOUT: Duration = Duration::from_secs(15);
+
+    fn pactl(args: &[&str]) -> String {
+        let output = Command::new("pactl")
+            .args(args)
+            .output()
+            .expect("pactl must be installed");
+        assert!(
+            output.status.success(),
+            "pactl {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    // A `module-null-sink`, unloaded when dropped
+    struct NullSink {
+        name: String,
+        module: Option<String>,
+    }
+
+    impl NullSink {
+        fn load(name: &str) -> Self {
+            let mut sink = Self {
+                name: name.to_string(),
+                module: None,
+            };
+            sink.reload();
+            sink
+        }
+
+        fn monitor(&self) -> String {
+            format!("{}.monitor", self.name)
+        }
+
+        fn reload(&mut self) {
+            let sink_name = format!("sink_name={}", self.name);
+            self.module = Some(pactl(&["load-module", "module-null-sink", &sink_name]));
+        }
+
+        fn unload(&mut self) {
+            if let Some(module) = self.module.take() {
+                pactl(&["unload-module", &module]);
+            }
+        }
+    }
+
+    impl Drop for NullSink {
+        fn drop(&mut self) {
+            if let Some(module) = self.module.take() {
+                let _ = Command::new("pactl")
+                    .args(["unload-module", &module])
+                    .status();
+            }
+        }
+    }
+
+    // Puts the user's default sink back after the test
+    struct DefaultSinkGuard(String);
+
+    impl Drop for DefaultSinkGuard {
+        fn drop(&mut self) {
+            let _ = Command::new("pactl")
+                .args(["set-default-sink", &self.0])
+                .status();
+        }
+    }
+
+    async fn next_change(changes: &mut UnboundedReceiver<DeviceChange>) -> DeviceChange {
+        tokio::time::timeout(CHANGE_TIMEOUT, changes.recv())
+            .await
+            .expect("no device change reported")
+            .expect("capture thread ended")
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a PulseAudio or PipeWire server with pactl"]
+    async fn follows_default_sink_changes() {
+        let _restore = DefaultSinkGuard(pactl(&["get-default-sink"]));
+        let first = NullSink::load("pluely_test_first");
+        let second = NullSink::load("pluely_test_second");
+        pactl(&["set-default-sink", &first.name]);
+
+        let mut stream = SpeakerInput::new(None).unwrap().stream();
+        let mut changes = stream.take_device_changes().unwrap();
+
+        pactl(&["set-default-sink", &second.name]);
+        let change = next_change(&mut changes).await;
+        assert_eq!(change.reason, DeviceChangeReason::DefaultChanged);
+        assert_eq!(change.device_id, second.monitor());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a PulseAudio or PipeWire server with pactl"]
+    async fn reconnects_when_device_returns() {
+        let mut sink = NullSink::load("pluely_test_removable");
+
+        let mut stream = SpeakerInput::new(Some(sink.monitor())).unwrap().stream();
+        let mut changes = stream.take_device_changes().unwrap();
+
+        sink.unload();
+        let change = next_change(&mut changes).await;
+        assert_eq!(change.reason, DeviceChangeReason::Lost);
+        assert_eq!(change.device_id, sink.monitor());
+
+        sink.reload();
+        let change = next_change(&mut changes).await;
+        assert_eq!(change.reason, DeviceChangeReason::Reconnected);
+        assert_eq!(change.device_id, sink.monitor());
+    }
+}
@@ -1,4 +1,5 @@
 // Pluely macos speaker input and stream
+use super::{AudioDevice, AudioDeviceKind};
 use anyhow::Result;
 use futures_util::Stream;
 use ringbuf::{
@@ -11,6 +12,31 @@ use std::task::{Poll, Waker};
 
 use ca::aggregate_device_keys as agg_keys;
 use cidre::{arc, av, cat, cf, core_audio as ca, ns, os};
+// The process tap always captures the system mix of the default output, so that is the
+// only device offered
+pub fn list_output_devices() -> Result<Vec<AudioDevice>> {
+    use cpal::traits::{DeviceTrait, HostTrait};
+
+    let Some(device) = cpal::default_host().default_output_device() else {
+        return Ok(Vec::new());
+    };
+    let (channels, sample_rate) = device
+        .default_output_config()
+        .map(|config| (config.channels(), config.sample_rate().0))
+        .unwrap_or((0, 0));
+
+    Ok(vec![AudioDevice {
+        id: "default".to_string(),
+        name: device
+            .name()
+            .unwrap_or_else(|_| "System output".to_string()),
+        kind: AudioDeviceKind::OutputMonitor,
+        is_default: true,
+        channels,
+        sample_rate,
+    }])
+}
+
 pub struct SpeakerInput {
     tap: ca::TapGuard, // Assuming ca::TapGuard from core-audio-rs
     agg_desc: arc::Retained<cf::DictionaryOf<cf::String, cf::Type>>,
@@ -1,27 +1,89 @@
 use anyhow::Result;
 use futures_util::Stream;
+use serde::Serialize;
 use std::pin::Pin;
 
 #[cfg(target_os = "macos")]
 mod macos;
 #[cfg(target_os = "macos")]
-use macos::{SpeakerInput as PlatformSpeakerInput, SpeakerStream as PlatformSpeakerStream};
+use macos::{
+    list_output_devices as platform_output_devices, SpeakerInput as PlatformSpeakerInput,
+    SpeakerStream as PlatformSpeakerStream,
+};
 
 #[cfg(target_os = "windows")]
 mod windows;
 #[cfg(target_os = "windows")]
-use windows::{SpeakerInput as PlatformSpeakerInput, SpeakerStream as PlatformSpeakerStream};
+use windows::{
+    list_output_devices as platform_output_devices, SpeakerInput as PlatformSpeakerInput,
+    SpeakerStream as PlatformSpeakerStream,
+};
 
 #[cfg(target_os = "linux")]
 mod linux;
 #[cfg(target_os = "linux")]
-use linux::{SpeakerInput as PlatformSpeakerInput, SpeakerStream as PlatformSpeakerStream};
+use linux::{
+    list_output_devices as platform_output_devices, SpeakerInput as PlatformSpeakerInput,
+    SpeakerStream as PlatformSpeakerStream,
+};
 
 mod commands;
+pub(crate) mod resample;
 
 // Re-export commands for tauri handler
 pub use commands::*;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioDeviceKind {
+    // Microphones and other capture devices, for `start_microphone_capture`
+    Input,
+    // Speaker outputs whose mix can be captured, for `start_system_audio_capture`
+    OutputMonitor,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioDevice {
+    // Pass as `device_id` to the start command matching `kind`
+    pub id: String,
+    pub name: String,
+    pub kind: AudioDeviceKind,
+    pub is_default: bool,
+    pub channels: u16,
+    pub sample_rate: u32,
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceChangeReason {
+    // The followed default output switched to another device
+    DefaultChanged,
+    // The device or the audio server went away; capture retries until it is back
+    Lost,
+    // Capture resumed after `Lost`
+    Reconnected,
+}
+
+// Payload of `audio-device-changed`. Only the Linux backend reports these for now.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceChange {
+    pub device_id: String,
+    pub reason: DeviceChangeReason,
+}
+
+// Output devices the platform backend can capture from
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+pub fn list_output_devices() -> Result<Vec<AudioDevice>> {
+    platform_output_devices()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+pub fn list_output_devices() -> Result<Vec<AudioDevice>> {
+    Ok(Vec::new())
+}
+
 // Pluely speaker input and stream
 pub struct SpeakerInput {
     #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
@@ -103,4 +165,16 @@ impl SpeakerStream {
         #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
         0
     }
+
+    // Device switches and reconnects seen by the capture thread. Can be taken once; `None`
+    // on backends that don't report them.
+    pub fn take_device_changes(
+        &mut self,
+    ) -> Option<tokio::sync::mpsc::UnboundedReceiver<DeviceChange>> {
+        #[cfg(target_os = "linux")]
+        return self.inner.take_device_changes();
+
+        #[cfg(not(target_os = "linux"))]
+        None
+    }
 }
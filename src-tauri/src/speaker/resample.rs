@@ -0,0 +1,394 @@
+// Sample rate conversion for captured audio, so VAD and encoding see the same rate on
+// every platform. A Kaiser-windowed sinc interpolator handles arbitrary rate pairs; the
+// kernel is tabulated once and linearly interpolated between table points.
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// Kernel table points per input sample
+const TABLE_OVERSAMPLE: usize = 512;
+// Input samples gathered from the inner stream before converting a block
+const BLOCK_SIZE: usize = 512;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResampleQuality {
+    // Short filter, at least 50 dB of alias rejection
+    Low,
+    // At least 70 dB, plenty for speech recognition
+    #[default]
+    Medium,
+    // At least 90 dB, with a narrower transition band
+    High,
+}
+
+impl ResampleQuality {
+    // Zero crossings per side, Kaiser beta, and passband edge as a fraction of the
+    // lower Nyquist frequency
+    fn params(self) -> (usize, f64, f64) {
+        match self {
+            ResampleQuality::Low => (8, 5.0, 0.85),
+            ResampleQuality::Medium => (16, 7.0, 0.9),
+            ResampleQuality::High => (32, 9.0, 0.94),
+        }
+    }
+}
+
+pub struct Resampler {
+    input_rate: u64,
+    output_rate: u64,
+    // Kernel half-width in input samples
+    half_width: f64,
+    // Kernel sampled at TABLE_OVERSAMPLE points per input sample, from 0 to half_width
+    table: Vec<f64>,
+    // Pending input; `history[0]` is absolute input index `history_start`
+    history: VecDeque<f32>,
+    history_start: u64,
+    next_output: u64,
+}
+
+impl Resampler {
+    pub fn new(input_rate: u32, output_rate: u32, quality: ResampleQuality) -> Self {
+        let (zero_crossings, beta, rolloff) = quality.params();
+        // Cutoff in cycles per input sample, relative to the input Nyquist frequency
+        let cutoff = rolloff * (output_rate as f64 / input_rate as f64).min(1.0);
+        let half_width = zero_crossings as f64 / cutoff;
+
+        let points = (half_width * TABLE_OVERSAMPLE as f64).ceil() as usize + 2;
+        let i0_beta = bessel_i0(beta);
+        let table = (0..points)
+            .map(|i| {
+                let t = i as f64 / TABLE_OVERSAMPLE as f64;
+                let x = t / half_width;
+                if x >= 1.0 {
+                    return 0.0;
+                }
+                let window = bessel_i0(beta * (1.0 - x * x).sqrt()) / i0_beta;
+                cutoff * sinc(cutoff * t) * window
+            })
+            .collect();
+
+        Self {
+            input_rate: input_rate as u64,
+            output_rate: output_rate as u64,
+            half_width,
+            table,
+            history: VecDeque::new(),
+            history_start: 0,
+            next_output: 0,
+        }
+    }
+
+    fn kernel(&self, t: f64) -> f64 {
+        let pos = t.abs() * TABLE_OVERSAMPLE as f64;
+        let index = pos as usize;
+        if index + 1 >= self.table.len() {
+            return 0.0;
+        }
+        let frac = pos - index as f64;
+        self.table[index] + (self.table[index + 1] - self.table[index]) * frac
+    }
+
+    // Converts the next block of input, appending every output sample that is complete.
+    // Output lags input by the kernel half-width (under 2 ms at 16 kHz and up).
+    pub fn process(&mut self, input: &[f32], output: &mut Vec<f32>) {
+        self.history.extend(input.iter().copied());
+        let reach = self.half_width.ceil() as u64;
+        let available = self.history_start + self.history.len() as u64;
+
+        loop {
+            // Exact position of the next output sample, in input samples
+            let numerator = self.next_output * self.input_rate;
+            let center = numerator / self.output_rate;
+            if center + reach >= available {
+                break;
+            }
+            let frac = (numerator % self.output_rate) as f64 / self.output_rate as f64;
+
+            // Samples before the first input are treated as silence
+            let first = center.saturating_sub(reach).max(self.history_start);
+            let last = center + reach;
+            let mut sum = 0.0f64;
+            for index in first..=last {
+                let offset = (center as f64 + frac) - index as f64;
+                let sample = self.history[(index - self.history_start) as usize];
+                sum += sample as f64 * self.kernel(offset);
+            }
+            output.push(sum as f32);
+            self.next_output += 1;
+        }
+
+        // Drop input no future output sample can reach
+        let center = self.next_output * self.input_rate / self.output_rate;
+        let keep_from = center.saturating_sub(reach);
+        while self.history_start < keep_from && !self.history.is_empty() {
+            self.history.pop_front();
+            self.history_start += 1;
+        }
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1.0
+    } else {
+        let px = std::f64::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+// Zeroth-order modified Bessel function of the first kind, for the Kaiser window
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half = x / 2.0;
+    for k in 1..50 {
+        term *= (half / k as f64) * (half / k as f64);
+        sum += term;
+        if term < sum * 1e-16 {
+            break;
+        }
+    }
+    sum
+}
+
+// Wraps a sample stream and converts it to `output_rate`. Passes samples through untouched
+// when the rates already match.
+pub struct ResampledStream<S> {
+    inner: S,
+    resampler: Option<Resampler>,
+    input: Vec<f32>,
+    output: VecDeque<f32>,
+    converted: Vec<f32>,
+    ended: bool,
+}
+
+impl<S> ResampledStream<S> {
+    pub fn new(inner: S, input_rate: u32, output_rate: u32, quality: ResampleQuality) -> Self {
+        let resampler =
+            (input_rate != output_rate).then(|| Resampler::new(input_rate, output_rate, quality));
+        Self {
+            inner,
+            resampler,
+            input: Vec::with_capacity(BLOCK_SIZE),
+            output: VecDeque::new(),
+            converted: Vec::new(),
+            ended: false,
+        }
+    }
+}
+
+impl<S: Stream<Item = f32> + Unpin> Stream for ResampledStream<S> {
+    type Item = f32;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let Some(resampler) = this.resampler.as_mut() else {
+            return Pin::new(&mut this.inner).poll_next(cx);
+        };
+
+        loop {
+            if let Some(sample) = this.output.pop_front() {
+                return Poll::Ready(Some(sample));
+            }
+            if this.ended {
+                return Poll::Ready(None);
+            }
+
+            // Take whatever the source has ready, up to one block
+            let mut pending = false;
+            while this.input.len() < BLOCK_SIZE {
+                match Pin::new(&mut this.inner).poll_next(cx) {
+                    Poll::Ready(Some(sample)) => this.input.push(sample),
+                    Poll::Ready(None) => {
+                        this.ended = true;
+                        break;
+                    }
+                    Poll::Pending => {
+                        pending = true;
+                        break;
+                    }
+                }
+            }
+
+            resampler.process(&this.input, &mut this.converted);
+            this.input.clear();
+            this.output.extend(this.converted.drain(..));
+
+            if this.output.is_empty() && pending {
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+    use std::f64::consts::PI;
+
+    fn sine(freq: f64, rate: u32, seconds: f64) -> Vec<f32> {
+        let len = (rate as f64 * seconds) as usize;
+        (0..len)
+            .map(|i| (2.0 * PI * freq * i as f64 / rate as f64).sin() as f32)
+            .collect()
+    }
+
+    fn resample(input: &[f32], from: u32, to: u32, quality: ResampleQuality) -> Vec<f32> {
+        let mut resampler = Resampler::new(from, to, quality);
+        let mut output = Vec::new();
+        resampler.process(input, &mut output);
+        output
+    }
+
+    // Amplitude of the `freq` component, measured past the filter's start-up transient
+    fn amplitude_at(samples: &[f32], freq: f64, rate: u32) -> f64 {
+        let settled = &samples[samples.len() / 4..];
+        let (mut re, mut im) = (0.0, 0.0);
+        for (i, &s) in settled.iter().enumerate() {
+            let phase = 2.0 * PI * freq * i as f64 / rate as f64;
+            re += s as f64 * phase.cos();
+            im += s as f64 * phase.sin();
+        }
+        2.0 * (re * re + im * im).sqrt() / settled.len() as f64
+    }
+
+    fn rms(samples: &[f32]) -> f64 {
+        let settled = &samples[samples.len() / 4..];
+        (settled.iter().map(|&s| (s as f64).powi(2)).sum::<f64>() / settled.len() as f64).sqrt()
+    }
+
+    fn db(ratio: f64) -> f64 {
+        20.0 * ratio.log10()
+    }
+
+    #[test]
+    fn passband_is_flat_when_downsampling() {
+        // Highest frequency each quality must pass within 0.1 dB at 16 kHz output
+        let cases = [
+            (ResampleQuality::Low, 5_000.0),
+            (ResampleQuality::Medium, 6_000.0),
+            (ResampleQuality::High, 6_800.0),
+        ];
+        for (quality, flat_to) in cases {
+            for freq in [100.0, 440.0, 1000.0, 3000.0, flat_to] {
+                let output = resample(&sine(freq, 44_100, 1.0), 44_100, 16_000, quality);
+                let gain = db(amplitude_at(&output, freq, 16_000));
+                assert!(
+                    gain.abs() < 0.1,
+                    "{:?}: {} Hz has {:.3} dB of gain",
+                    quality,
+                    freq,
+                    gain
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn passband_is_flat_when_upsampling() {
+        for freq in [200.0, 1000.0, 3000.0] {
+            let output = resample(
+                &sine(freq, 16_000, 1.0),
+                16_000,
+                48_000,
+                ResampleQuality::Medium,
+            );
+            let gain = db(amplitude_at(&output, freq, 48_000));
+            assert!(gain.abs() < 0.1, "{} Hz has {:.3} dB of gain", freq, gain);
+        }
+    }
+
+    #[test]
+    fn rejects_aliases_above_output_nyquist() {
+        let cases = [
+            (ResampleQuality::Low, -50.0),
+            (ResampleQuality::Medium, -70.0),
+            (ResampleQuality::High, -90.0),
+        ];
+        for (quality, limit) in cases {
+            // Each would fold back into the 0-8 kHz band without filtering
+            for freq in [9_500.0, 12_000.0, 15_000.0, 20_000.0] {
+                let output = resample(&sine(freq, 44_100, 1.0), 44_100, 16_000, quality);
+                let level = db(rms(&output) * 2f64.sqrt());
+                assert!(
+                    level < limit,
+                    "{:?}: {} Hz leaks through at {:.1} dB",
+                    quality,
+                    freq,
+                    level
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_images_when_upsampling() {
+        let output = resample(
+            &sine(1000.0, 16_000, 1.0),
+            16_000,
+            48_000,
+            ResampleQuality::Medium,
+        );
+        // Zero-stuffing would leave images at 15 and 17 kHz
+        for image in [15_000.0, 17_000.0] {
+            let level = db(amplitude_at(&output, image, 48_000));
+            assert!(level < -65.0, "{} Hz image at {:.1} dB", image, level);
+        }
+    }
+
+    #[test]
+    fn block_size_does_not_change_output() {
+        let input = sine(1000.0, 48_000, 0.5);
+        let whole = resample(&input, 48_000, 16_000, ResampleQuality::High);
+
+        let mut resampler = Resampler::new(48_000, 16_000, ResampleQuality::High);
+        let mut chunked = Vec::new();
+        for chunk in input.chunks(37) {
+            resampler.process(chunk, &mut chunked);
+        }
+
+        assert_eq!(whole, chunked);
+    }
+
+    #[test]
+    fn output_length_follows_rate_ratio() {
+        let input = sine(1000.0, 44_100, 2.0);
+        let output = resample(&input, 44_100, 16_000, ResampleQuality::Medium);
+        let expected = 32_000;
+        // Only the kernel half-width is held back at the end
+        assert!(output.len() <= expected && output.len() > expected - 64);
+    }
+
+    #[tokio::test]
+    async fn stream_passes_through_matching_rates() {
+        let input = sine(1000.0, 16_000, 0.1);
+        let stream = ResampledStream::new(
+            futures_util::stream::iter(input.clone()),
+            16_000,
+            16_000,
+            ResampleQuality::Medium,
+        );
+        let output: Vec<f32> = stream.collect().await;
+        assert_eq!(input, output);
+    }
+
+    #[tokio::test]
+    async fn stream_matches_resampler() {
+        let input = sine(1000.0, 44_100, 0.5);
+        let stream = ResampledStream::new(
+            futures_util::stream::iter(input.clone()),
+            44_100,
+            16_000,
+            ResampleQuality::Medium,
+        );
+        let output: Vec<f32> = stream.collect().await;
+        assert_eq!(
+            output,
+            resample(&input, 44_100, 16_000, ResampleQuality::Medium)
+        );
+    }
+}
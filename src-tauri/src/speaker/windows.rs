@@ -1,4 +1,5 @@
 // Pluely windows speaker input and stream
+use super::{AudioDevice, AudioDeviceKind};
 use anyhow::Result;
 use futures_util::Stream;
 use std::collections::VecDeque;
@@ -9,6 +10,48 @@ use std::time::Duration;
 use tracing::error;
 use wasapi::{get_default_device, Direction, SampleType, StreamMode, WaveFormat};
 
+// IDs are `windows_output_<index>` into the active render device collection, which is
+// what `SpeakerInput::new` expects
+pub fn list_output_devices() -> Result<Vec<AudioDevice>> {
+    use wasapi::DeviceCollection;
+
+    // Listing may run on a thread that hasn't initialized COM yet
+    let _ = wasapi::initialize_mta();
+
+    let default_id = get_default_device(&Direction::Render)
+        .and_then(|device| device.get_id())
+        .ok();
+    let collection = DeviceCollection::new(&Direction::Render)?;
+    let mut devices = Vec::new();
+
+    for index in 0..collection.get_nbr_devices()? {
+        let device = collection.get_device_at_index(index)?;
+        let format = device
+            .get_iaudioclient()
+            .and_then(|client| client.get_mixformat());
+        let (channels, sample_rate) = match format {
+            Ok(format) => (format.get_nchannels(), format.get_samplespersec()),
+            Err(e) => {
+                error!("Failed to read format of output device {}: {}", index, e);
+                (0, 0)
+            }
+        };
+
+        devices.push(AudioDevice {
+            id: format!("windows_output_{}", index),
+            name: device
+                .get_friendlyname()
+                .unwrap_or_else(|_| format!("Output {}", index + 1)),
+            kind: AudioDeviceKind::OutputMonitor,
+            is_default: device.get_id().ok() == default_id,
+            channels,
+            sample_rate,
+        });
+    }
+
+    Ok(devices)
+}
+
 pub struct SpeakerInput {
     device_index: Option<usize>,
 }
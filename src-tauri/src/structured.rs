@@ -0,0 +1,762 @@
+// Structured (JSON-schema constrained) chat answers
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+// Name of the built-in interview helper schema
+pub const INTERVIEW_ANSWER_SCHEMA: &str = "interview_answer";
+
+// How the schema is enforced on the provider side
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StructuredOutputMode {
+    // Pick based on the selected provider
+    #[default]
+    Auto,
+    // OpenAI-compatible `response_format: { type: "json_schema" }`
+    ResponseFormat,
+    // Single forced tool call whose arguments follow the schema
+    Tool,
+}
+
+// Structured output options sent by the frontend with `chat_stream_response`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuredOutputRequest {
+    pub name: String,
+    // Omit to use a built-in schema by name (e.g. "interview_answer")
+    #[serde(default)]
+    pub schema: Option<Value>,
+    #[serde(default)]
+    pub mode: StructuredOutputMode,
+    #[serde(default = "default_strict")]
+    pub strict: bool,
+}
+
+fn default_strict() -> bool {
+    true
+}
+
+// Answer shape for the interview helper prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterviewAnswer {
+    pub code: String,
+    pub complexity: String,
+    pub explanation: String,
+}
+
+// Final typed answer emitted once the stream completes
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+pub enum StructuredAnswer {
+    InterviewAnswer(InterviewAnswer),
+    Json(Value),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StructuredOutputResult {
+    pub name: String,
+    pub answer: StructuredAnswer,
+}
+
+// Resolved structured output settings for a single request
+#[derive(Debug, Clone)]
+pub struct StructuredOutput {
+    name: String,
+    schema: Value,
+    mode: StructuredOutputMode,
+    strict: bool,
+    // Anthropic's tool format differs from the OpenAI one
+    anthropic: bool,
+}
+
+impl StructuredOutputRequest {
+    pub fn resolve(self, provider: Option<&str>) -> Result<StructuredOutput, String> {
+        let name = self.name.trim().to_string();
+        if name.is_empty() {
+            return Err("Structured output requires a schema name".to_string());
+        }
+
+        let schema = match self.schema {
+            Some(schema) => schema,
+            None => builtin_schema(&name)
+                .ok_or_else(|| format!("Unknown structured output schema: {}", name))?,
+        };
+
+        if !schema.is_object() {
+            return Err("Structured output schema must be a JSON object".to_string());
+        }
+
+        let anthropic = is_anthropic(provider);
+        let mode = match self.mode {
+            StructuredOutputMode::Auto if anthropic => StructuredOutputMode::Tool,
+            StructuredOutputMode::Auto => StructuredOutputMode::ResponseFormat,
+            mode => mode,
+        };
+
+        Ok(StructuredOutput {
+            name,
+            schema,
+            mode,
+            strict: self.strict,
+            anthropic,
+        })
+    }
+}
+
+fn builtin_schema(name: &str) -> Option<Value> {
+    match name {
+        INTERVIEW_ANSWER_SCHEMA => Some(json!({
+            "type": "object",
+            "properties": {
+                "code": {
+                    "type": "string",
+                    "description": "Complete solution code, or an empty string when no code is needed"
+                },
+                "complexity": {
+                    "type": "string",
+                    "description": "Time and space complexity, e.g. \"O(n) time, O(1) space\""
+                },
+                "explanation": {
+                    "type": "string",
+                    "description": "Short explanation of the approach in plain language"
+                }
+            },
+            "required": ["code", "complexity", "explanation"],
+            "additionalProperties": false
+        })),
+        _ => None,
+    }
+}
+
+// Anthropic has no `response_format`, so `Auto` uses the forced tool call there
+fn is_anthropic(provider: Option<&str>) -> bool {
+    let provider = provider.unwrap_or_default().to_lowercase();
+    provider.contains("anthropic") || provider.contains("claude")
+}
+
+impl StructuredOutput {
+    // Adds the provider-specific schema constraint to the chat request body
+    pub fn apply_to_body(&self, request_body: &mut Value) {
+        let Some(body) = request_body.as_object_mut() else {
+            return;
+        };
+
+        match self.mode {
+            StructuredOutputMode::Tool if self.anthropic => {
+                body.remove("response_format");
+                body.insert(
+                    "tools".to_string(),
+                    json!([{
+                        "name": self.name,
+                        "description": "Return the answer using this exact structure",
+                        "input_schema": self.schema
+                    }]),
+                );
+                body.insert(
+                    "tool_choice".to_string(),
+                    json!({ "type": "tool", "name": self.name }),
+                );
+            }
+            StructuredOutputMode::Tool => {
+                body.remove("response_format");
+                body.insert(
+                    "tools".to_string(),
+                    json!([{
+                        "type": "function",
+                        "function": {
+                            "name": self.name,
+                            "description": "Return the answer using this exact structure",
+                            "parameters": self.schema
+                        }
+                    }]),
+                );
+                body.insert(
+                    "tool_choice".to_string(),
+                    json!({ "type": "function", "function": { "name": self.name } }),
+                );
+            }
+            _ => {
+                body.insert(
+                    "response_format".to_string(),
+                    json!({
+                        "type": "json_schema",
+                        "json_schema": {
+                            "name": self.name,
+                            "schema": self.schema,
+                            "strict": self.strict
+                        }
+                    }),
+                );
+            }
+        }
+    }
+
+    // Parses and validates the complete response, then converts it into a typed answer
+    pub fn finish(&self, full_response: &str) -> Result<StructuredOutputResult, String> {
+        let value: Value = serde_json::from_str(strip_code_fence(full_response))
+            .map_err(|e| format!("Structured output is not valid JSON: {}", e))?;
+
+        let mut errors = Vec::new();
+        validate_against_schema(&value, &self.schema, "$", &mut errors);
+        if !errors.is_empty() {
+            return Err(format!(
+                "Structured output does not match schema: {}",
+                errors.join("; ")
+            ));
+        }
+
+        let answer = if self.name == INTERVIEW_ANSWER_SCHEMA {
+            let typed: InterviewAnswer = serde_json::from_value(value)
+                .map_err(|e| format!("Failed to read interview answer: {}", e))?;
+            StructuredAnswer::InterviewAnswer(typed)
+        } else {
+            StructuredAnswer::Json(value)
+        };
+
+        Ok(StructuredOutputResult {
+            name: self.name.clone(),
+            answer,
+        })
+    }
+}
+
+fn strip_code_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else {
+        return trimmed;
+    };
+    let body = rest.split_once('\n').map(|(_, body)| body).unwrap_or("");
+    body.trim_end().trim_end_matches('`').trim()
+}
+
+// Validates the subset of JSON schema used for structured answers:
+// type, properties, required, additionalProperties, items and enum.
+fn validate_against_schema(value: &Value, schema: &Value, path: &str, errors: &mut Vec<String>) {
+    if let Some(expected) = schema.get("type") {
+        let matches = match expected {
+            Value::String(t) => value_has_type(value, t),
+            Value::Array(types) => types
+                .iter()
+                .filter_map(|t| t.as_str())
+                .any(|t| value_has_type(value, t)),
+            _ => true,
+        };
+        if !matches {
+            errors.push(format!("{} should be of type {}", path, expected));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !allowed.contains(value) {
+            errors.push(format!("{} is not one of the allowed values", path));
+        }
+    }
+
+    if let Value::Object(map) = value {
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+
+        if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+            for key in required.iter().filter_map(|k| k.as_str()) {
+                if !map.contains_key(key) {
+                    errors.push(format!("{} is missing required field '{}'", path, key));
+                }
+            }
+        }
+
+        for (key, field) in map {
+            let field_path = format!("{}.{}", path, key);
+            match properties.and_then(|p| p.get(key)) {
+                Some(field_schema) => {
+                    validate_against_schema(field, field_schema, &field_path, errors)
+                }
+                None => {
+                    if schema.get("additionalProperties") == Some(&Value::Bool(false)) {
+                        errors.push(format!("{} is not allowed", field_path));
+                    }
+                }
+            }
+        }
+    }
+
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (index, item) in items.iter().enumerate() {
+            validate_against_schema(item, item_schema, &format!("{}[{}]", path, index), errors);
+        }
+    }
+}
+
+fn value_has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frame {
+    Object,
+    Array,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Lex {
+    // Leading whitespace or a ```json fence before the top-level value
+    Start,
+    InFence,
+    ExpectValue,
+    ExpectValueOrClose,
+    ExpectKeyOrClose,
+    ExpectKey,
+    ExpectColon,
+    AfterValue,
+    InString {
+        is_key: bool,
+        escape: bool,
+        unicode_left: u8,
+    },
+    InLiteral {
+        rest: &'static str,
+    },
+    InNumber {
+        start: usize,
+    },
+    Done,
+}
+
+// Incremental validator for streamed JSON. Every pushed delta must keep the text a valid
+// JSON prefix, and `snapshot` closes the open structures to preview the partial object.
+pub struct PartialJson {
+    text: String,
+    value_start: usize,
+    key_start: usize,
+    stack: Vec<Frame>,
+    lex: Lex,
+}
+
+impl Default for PartialJson {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialJson {
+    pub fn new() -> Self {
+        Self {
+            text: String::new(),
+            value_start: 0,
+            key_start: 0,
+            stack: Vec::new(),
+            lex: Lex::Start,
+        }
+    }
+
+    pub fn push(&mut self, delta: &str) -> Result<(), String> {
+        for c in delta.chars() {
+            self.push_char(c)?;
+            self.text.push(c);
+        }
+        Ok(())
+    }
+
+    fn push_char(&mut self, c: char) -> Result<(), String> {
+        let position = self.text.len();
+        match self.lex.clone() {
+            Lex::Start => {
+                if c == '`' {
+                    self.lex = Lex::InFence;
+                } else if !c.is_whitespace() {
+                    self.value_start = position;
+                    self.start_value(c, position)?;
+                }
+            }
+            Lex::InFence => {
+                if c == '\n' {
+                    self.lex = Lex::Start;
+                }
+            }
+            Lex::ExpectValue => {
+                if !c.is_whitespace() {
+                    self.start_value(c, position)?;
+                }
+            }
+            Lex::ExpectValueOrClose => {
+                if c == ']' {
+                    self.close(Frame::Array)?;
+                } else if !c.is_whitespace() {
+                    self.start_value(c, position)?;
+                }
+            }
+            Lex::ExpectKeyOrClose => match c {
+                '}' => self.close(Frame::Object)?,
+                '"' => self.enter_key(position),
+                c if c.is_whitespace() => {}
+                c => return Err(unexpected(c, "an object key")),
+            },
+            Lex::ExpectKey => match c {
+                '"' => self.enter_key(position),
+                c if c.is_whitespace() => {}
+                c => return Err(unexpected(c, "an object key")),
+            },
+            Lex::ExpectColon => match c {
+                ':' => self.lex = Lex::ExpectValue,
+                c if c.is_whitespace() => {}
+                c => return Err(unexpected(c, "':'")),
+            },
+            Lex::AfterValue => self.after_value(c)?,
+            Lex::InString {
+                is_key,
+                escape,
+                unicode_left,
+            } => {
+                if unicode_left > 0 {
+                    if !c.is_ascii_hexdigit() {
+                        return Err(unexpected(c, "a hex digit"));
+                    }
+                    self.lex = Lex::InString {
+                        is_key,
+                        escape: false,
+                        unicode_left: unicode_left - 1,
+                    };
+                } else if escape {
+                    let unicode_left = match c {
+                        'u' => 4,
+                        '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' => 0,
+                        c => return Err(unexpected(c, "an escape sequence")),
+                    };
+                    self.lex = Lex::InString {
+                        is_key,
+                        escape: false,
+                        unicode_left,
+                    };
+                } else if c == '\\' {
+                    self.lex = Lex::InString {
+                        is_key,
+                        escape: true,
+                        unicode_left: 0,
+                    };
+                } else if c == '"' {
+                    self.lex = if is_key {
+                        Lex::ExpectColon
+                    } else {
+                        self.value_finished()
+                    };
+                } else if c.is_control() {
+                    return Err("Unescaped control character in string".to_string());
+                }
+            }
+            Lex::InLiteral { rest } => {
+                let mut chars = rest.chars();
+                if chars.next() != Some(c) {
+                    return Err(unexpected(c, "a JSON literal"));
+                }
+                let rest = chars.as_str();
+                self.lex = if rest.is_empty() {
+                    self.value_finished()
+                } else {
+                    Lex::InLiteral { rest }
+                };
+            }
+            Lex::InNumber { .. } => {
+                if !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+                    self.lex = self.value_finished();
+                    self.after_value(c)?;
+                }
+            }
+            Lex::Done => {
+                if !c.is_whitespace() && c != '`' {
+                    return Err(unexpected(c, "end of output"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn start_value(&mut self, c: char, position: usize) -> Result<(), String> {
+        self.lex = match c {
+            '{' => {
+                self.stack.push(Frame::Object);
+                Lex::ExpectKeyOrClose
+            }
+            '[' => {
+                self.stack.push(Frame::Array);
+                Lex::ExpectValueOrClose
+            }
+            '"' => Lex::InString {
+                is_key: false,
+                escape: false,
+                unicode_left: 0,
+            },
+            't' => Lex::InLiteral { rest: "rue" },
+            'f' => Lex::InLiteral { rest: "alse" },
+            'n' => Lex::InLiteral { rest: "ull" },
+            c if c == '-' || c.is_ascii_digit() => Lex::InNumber { start: position },
+            c => return Err(unexpected(c, "a JSON value")),
+        };
+        Ok(())
+    }
+
+    fn enter_key(&mut self, position: usize) {
+        self.key_start = position;
+        self.lex = Lex::InString {
+            is_key: true,
+            escape: false,
+            unicode_left: 0,
+        };
+    }
+
+    fn after_value(&mut self, c: char) -> Result<(), String> {
+        match (c, self.stack.last()) {
+            (c, _) if c.is_whitespace() => {}
+            (',', Some(Frame::Object)) => self.lex = Lex::ExpectKey,
+            (',', Some(Frame::Array)) => self.lex = Lex::ExpectValue,
+            ('}', Some(Frame::Object)) => self.close(Frame::Object)?,
+            (']', Some(Frame::Array)) => self.close(Frame::Array)?,
+            (c, _) => return Err(unexpected(c, "',' or a closing bracket")),
+        }
+        Ok(())
+    }
+
+    fn close(&mut self, frame: Frame) -> Result<(), String> {
+        if self.stack.pop() != Some(frame) {
+            return Err("Mismatched closing bracket".to_string());
+        }
+        self.lex = self.value_finished();
+        Ok(())
+    }
+
+    fn value_finished(&self) -> Lex {
+        if self.stack.is_empty() {
+            Lex::Done
+        } else {
+            Lex::AfterValue
+        }
+    }
+
+    // Best-effort parse of the partial text with all open strings and brackets closed
+    pub fn snapshot(&self) -> Option<Value> {
+        let mut text = self.text.get(self.value_start..)?.to_string();
+
+        match &self.lex {
+            Lex::Start | Lex::InFence => return None,
+            Lex::InString { is_key: true, .. } | Lex::ExpectColon => {
+                // Drop the half-received key
+                text.truncate(self.key_start - self.value_start);
+                trim_trailing_comma(&mut text);
+            }
+            Lex::InString {
+                escape,
+                unicode_left,
+                ..
+            } => {
+                if *unicode_left > 0 {
+                    // Drop the incomplete `\uXXXX` sequence
+                    let keep = text.len() - (6 - *unicode_left as usize);
+                    text.truncate(keep);
+                } else if *escape {
+                    text.pop();
+                }
+                text.push('"');
+            }
+            Lex::InLiteral { rest } => text.push_str(rest),
+            Lex::InNumber { start } => {
+                let start = start - self.value_start;
+                while text.len() > start && !text.ends_with(|c: char| c.is_ascii_digit()) {
+                    text.pop();
+                }
+                if text.len() == start {
+                    text.push_str("null");
+                }
+            }
+            Lex::ExpectValue => match self.stack.last() {
+                Some(Frame::Object) => text.push_str("null"),
+                Some(Frame::Array) => trim_trailing_comma(&mut text),
+                None => return None,
+            },
+            Lex::ExpectKey => trim_trailing_comma(&mut text),
+            Lex::ExpectValueOrClose | Lex::ExpectKeyOrClose | Lex::AfterValue | Lex::Done => {}
+        }
+
+        for frame in self.stack.iter().rev() {
+            text.push(match frame {
+                Frame::Object => '}',
+                Frame::Array => ']',
+            });
+        }
+
+        serde_json::from_str(text.trim_end_matches(['`', ' ', '\n', '\r', '\t'])).ok()
+    }
+}
+
+fn trim_trailing_comma(text: &mut String) {
+    let trimmed_len = text.trim_end().len();
+    text.truncate(trimmed_len);
+    if text.ends_with(',') {
+        text.pop();
+    }
+}
+
+fn unexpected(c: char, expected: &str) -> String {
+    format!(
+        "Unexpected '{}' while expecting {}",
+        c.escape_default(),
+        expected
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interview_output(provider: Option<&str>) -> StructuredOutput {
+        StructuredOutputRequest {
+            name: INTERVIEW_ANSWER_SCHEMA.to_string(),
+            schema: None,
+            mode: StructuredOutputMode::Auto,
+            strict: true,
+        }
+        .resolve(provider)
+        .unwrap()
+    }
+
+    fn push_all(partial: &mut PartialJson, deltas: &[&str]) -> Result<(), String> {
+        deltas.iter().try_for_each(|delta| partial.push(delta))
+    }
+
+    #[test]
+    fn snapshots_close_open_structures() {
+        let mut partial = PartialJson::new();
+        push_all(&mut partial, &["{\"code\": \"fn ma", "in()"]).unwrap();
+        assert_eq!(partial.snapshot(), Some(json!({ "code": "fn main()" })));
+
+        push_all(&mut partial, &["\", \"compl"]).unwrap();
+        assert_eq!(partial.snapshot(), Some(json!({ "code": "fn main()" })));
+
+        push_all(&mut partial, &["exity\": "]).unwrap();
+        assert_eq!(
+            partial.snapshot(),
+            Some(json!({ "code": "fn main()", "complexity": null }))
+        );
+
+        push_all(&mut partial, &["\"O(n)\", \"tags\": [1, 2"]).unwrap();
+        assert_eq!(
+            partial.snapshot(),
+            Some(json!({ "code": "fn main()", "complexity": "O(n)", "tags": [1, 2] }))
+        );
+
+        push_all(&mut partial, &[", tr"]).unwrap();
+        assert_eq!(
+            partial.snapshot(),
+            Some(json!({ "code": "fn main()", "complexity": "O(n)", "tags": [1, 2, true] }))
+        );
+
+        push_all(&mut partial, &["ue]}"]).unwrap();
+        assert_eq!(
+            partial.snapshot(),
+            Some(json!({ "code": "fn main()", "complexity": "O(n)", "tags": [1, 2, true] }))
+        );
+    }
+
+    #[test]
+    fn snapshots_drop_incomplete_escapes() {
+        let mut partial = PartialJson::new();
+        push_all(&mut partial, &["{\"a\": \"x\\u00"]).unwrap();
+        assert_eq!(partial.snapshot(), Some(json!({ "a": "x" })));
+
+        push_all(&mut partial, &["e9\\"]).unwrap();
+        assert_eq!(partial.snapshot(), Some(json!({ "a": "xé" })));
+    }
+
+    #[test]
+    fn code_fences_are_skipped() {
+        let mut partial = PartialJson::new();
+        push_all(&mut partial, &["```json\n", "{\"a\": 1}", "\n```"]).unwrap();
+        assert_eq!(partial.snapshot(), Some(json!({ "a": 1 })));
+        assert_eq!(strip_code_fence("```json\n{\"a\": 1}\n```"), "{\"a\": 1}");
+    }
+
+    #[test]
+    fn malformed_prefixes_are_rejected() {
+        for deltas in [
+            &["{\"a\" 1"][..],
+            &["{a: 1}"],
+            &["[1, 2}"],
+            &["{\"a\": tru", "x"],
+            &["{\"a\": \"\\q\"}"],
+            &["{\"a\": 1} x"],
+        ] {
+            let mut partial = PartialJson::new();
+            assert!(push_all(&mut partial, deltas).is_err(), "{:?}", deltas);
+        }
+    }
+
+    #[test]
+    fn finish_accepts_matching_answers() {
+        let output = interview_output(None);
+        let result = output
+            .finish("{\"code\": \"\", \"complexity\": \"O(1)\", \"explanation\": \"none\"}")
+            .unwrap();
+        assert!(matches!(
+            result.answer,
+            StructuredAnswer::InterviewAnswer(InterviewAnswer { ref complexity, .. })
+                if complexity == "O(1)"
+        ));
+    }
+
+    #[test]
+    fn finish_rejects_schema_violations() {
+        let output = interview_output(None);
+        for response in [
+            "{\"code\": \"\", \"complexity\": \"O(1)\"}",
+            "{\"code\": 1, \"complexity\": \"O(1)\", \"explanation\": \"\"}",
+            "{\"code\": \"\", \"complexity\": \"\", \"explanation\": \"\", \"extra\": 1}",
+            "[]",
+            "{\"code\": \"\"",
+        ] {
+            assert!(output.finish(response).is_err(), "{}", response);
+        }
+    }
+
+    #[test]
+    fn nested_schemas_are_validated() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "level": { "enum": ["easy", "hard"] },
+                "steps": { "type": "array", "items": { "type": "integer" } }
+            }
+        });
+        let mut errors = Vec::new();
+        validate_against_schema(
+            &json!({ "level": "medium", "steps": [1, "two"] }),
+            &schema,
+            "$",
+            &mut errors,
+        );
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+    }
+
+    #[test]
+    fn tool_mode_follows_the_provider_format() {
+        let mut body = json!({});
+        interview_output(Some("anthropic")).apply_to_body(&mut body);
+        assert!(body["tools"][0]["input_schema"].is_object());
+        assert_eq!(body["tool_choice"]["type"], "tool");
+
+        let mut body = json!({});
+        let mut output = interview_output(Some("openai"));
+        output.mode = StructuredOutputMode::Tool;
+        output.apply_to_body(&mut body);
+        assert!(body["tools"][0]["function"]["parameters"].is_object());
+        assert_eq!(body["tool_choice"]["type"], "function");
+
+        let mut body = json!({});
+        interview_output(Some("openai")).apply_to_body(&mut body);
+        assert_eq!(body["response_format"]["type"], "json_schema");
+    }
+}
@@ -0,0 +1,250 @@
+// Transcription results cached by audio hash, so retried chats and re-sent utterances
+// don't transcribe the same audio again
+use crate::api::AudioResponse;
+use crate::chunking::TranscriptSegment;
+use crate::db::get_pool;
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Sqlite};
+use tauri::AppHandle;
+
+const MAX_ENTRIES: i64 = 500;
+const MAX_AGE_MS: i64 = 30 * 24 * 60 * 60 * 1000;
+
+pub struct CacheKey {
+    audio_hash: String,
+    model: String,
+    settings_hash: String,
+}
+
+impl CacheKey {
+    // `model` identifies the transcription endpoint(s) and model(s) that produce the result;
+    // `settings` is anything else that changes it (language setting, vocabulary)
+    pub fn new(audio_bytes: &[u8], model: &str, settings: &str) -> Self {
+        Self {
+            audio_hash: hex_digest(audio_bytes),
+            model: model.to_string(),
+            settings_hash: hex_digest(settings.as_bytes()),
+        }
+    }
+}
+
+fn hex_digest(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub async fn lookup(app: &AppHandle, key: &CacheKey) -> Option<AudioResponse> {
+    let pool = get_pool(app).await.ok()?;
+    lookup_in(&pool, key, chrono::Utc::now().timestamp_millis()).await
+}
+
+// Stores a successful result, then drops expired entries and the least recently used
+// ones beyond `MAX_ENTRIES`
+pub async fn store(app: &AppHandle, key: &CacheKey, response: &AudioResponse) {
+    let Ok(pool) = get_pool(app).await else {
+        return;
+    };
+    store_in(&pool, key, response, chrono::Utc::now().timestamp_millis()).await;
+}
+
+async fn lookup_in(pool: &Pool<Sqlite>, key: &CacheKey, now: i64) -> Option<AudioResponse> {
+    let (transcription, language, segments): (String, Option<String>, String) = sqlx::query_as(
+        "SELECT transcription, language, segments FROM transcription_cache
+         WHERE audio_hash = ? AND model = ? AND settings_hash = ? AND created_at >= ?",
+    )
+    .bind(&key.audio_hash)
+    .bind(&key.model)
+    .bind(&key.settings_hash)
+    .bind(now - MAX_AGE_MS)
+    .fetch_optional(pool)
+    .await
+    .ok()??;
+
+    let _ = sqlx::query(
+        "UPDATE transcription_cache SET last_used_at = ?
+         WHERE audio_hash = ? AND model = ? AND settings_hash = ?",
+    )
+    .bind(now)
+    .bind(&key.audio_hash)
+    .bind(&key.model)
+    .bind(&key.settings_hash)
+    .execute(pool)
+    .await;
+
+    Some(AudioResponse {
+        success: true,
+        transcription: Some(transcription),
+        error: None,
+        language,
+        segments: serde_json::from_str::<Vec<TranscriptSegment>>(&segments).unwrap_or_default(),
+    })
+}
+
+async fn store_in(pool: &Pool<Sqlite>, key: &CacheKey, response: &AudioResponse, now: i64) {
+    let Some(transcription) = response
+        .transcription
+        .as_ref()
+        .filter(|t| !t.trim().is_empty())
+    else {
+        return;
+    };
+    let segments = serde_json::to_string(&response.segments).unwrap_or_else(|_| "[]".to_string());
+
+    let result = sqlx::query(
+        "INSERT OR REPLACE INTO transcription_cache
+         (audio_hash, model, settings_hash, transcription, language, segments, created_at, last_used_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&key.audio_hash)
+    .bind(&key.model)
+    .bind(&key.settings_hash)
+    .bind(transcription)
+    .bind(&response.language)
+    .bind(segments)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await;
+    if let Err(e) = result {
+        tracing::warn!("Failed to cache transcription: {}", e);
+        return;
+    }
+
+    let _ = sqlx::query("DELETE FROM transcription_cache WHERE created_at < ?")
+        .bind(now - MAX_AGE_MS)
+        .execute(pool)
+        .await;
+    let _ = sqlx::query(
+        "DELETE FROM transcription_cache WHERE rowid IN (
+            SELECT rowid FROM transcription_cache ORDER BY last_used_at DESC LIMIT -1 OFFSET ?
+         )",
+    )
+    .bind(MAX_ENTRIES)
+    .execute(pool)
+    .await;
+}
+
+async fn clear(pool: &Pool<Sqlite>) -> Result<u64, String> {
+    sqlx::query("DELETE FROM transcription_cache")
+        .execute(pool)
+        .await
+        .map(|result| result.rows_affected())
+        .map_err(|e| format!("Failed to clear transcription cache: {}", e))
+}
+
+// Returns the number of removed entries
+#[tauri::command]
+pub async fn clear_transcription_cache(app: AppHandle) -> Result<u64, String> {
+    let pool = get_pool(&app).await?;
+    clear(&pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    const NOW: i64 = 1_700_000_000_000;
+
+    async fn pool() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::raw_sql(include_str!("db/migrations/transcription-cache.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    fn response(text: &str) -> AudioResponse {
+        AudioResponse {
+            success: true,
+            transcription: Some(text.to_string()),
+            error: None,
+            language: Some("en".to_string()),
+            segments: vec![TranscriptSegment {
+                start_ms: 0,
+                end_ms: 1000,
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    async fn count(pool: &Pool<Sqlite>) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM transcription_cache")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn stored_results_are_found_by_audio_model_and_settings() {
+        let pool = pool().await;
+        let key = CacheKey::new(b"audio", "whisper-1@url", "auto");
+        assert!(lookup_in(&pool, &key, NOW).await.is_none());
+
+        store_in(&pool, &key, &response("hello"), NOW).await;
+        let cached = lookup_in(&pool, &key, NOW + 1).await.unwrap();
+        assert_eq!(cached.transcription.as_deref(), Some("hello"));
+        assert_eq!(cached.language.as_deref(), Some("en"));
+        assert_eq!(cached.segments.len(), 1);
+
+        for other in [
+            CacheKey::new(b"other audio", "whisper-1@url", "auto"),
+            CacheKey::new(b"audio", "gpt-4o-transcribe@url", "auto"),
+            CacheKey::new(b"audio", "whisper-1@url", "de"),
+        ] {
+            assert!(lookup_in(&pool, &other, NOW).await.is_none());
+        }
+
+        // Expired entries are not returned
+        assert!(lookup_in(&pool, &key, NOW + MAX_AGE_MS + 1).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn empty_transcripts_are_not_stored() {
+        let pool = pool().await;
+        let key = CacheKey::new(b"audio", "whisper-1@url", "auto");
+        store_in(&pool, &key, &response("  "), NOW).await;
+        assert_eq!(count(&pool).await, 0);
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recently_used_and_expired_entries() {
+        let pool = pool().await;
+        let key = |i: i64| CacheKey::new(&i.to_le_bytes(), "whisper-1@url", "auto");
+
+        let stale = CacheKey::new(b"stale", "whisper-1@url", "auto");
+        store_in(&pool, &stale, &response("stale"), NOW - MAX_AGE_MS - 1).await;
+        for i in 0..MAX_ENTRIES {
+            store_in(&pool, &key(i), &response("text"), NOW + i).await;
+        }
+        assert_eq!(count(&pool).await, MAX_ENTRIES);
+        assert!(lookup_in(&pool, &stale, NOW).await.is_none());
+
+        // Using the oldest entry keeps it; the next oldest goes instead
+        let later = NOW + MAX_ENTRIES;
+        assert!(lookup_in(&pool, &key(0), later).await.is_some());
+        store_in(&pool, &key(MAX_ENTRIES), &response("text"), later + 1).await;
+        assert_eq!(count(&pool).await, MAX_ENTRIES);
+        assert!(lookup_in(&pool, &key(0), later).await.is_some());
+        assert!(lookup_in(&pool, &key(1), later).await.is_none());
+        assert!(lookup_in(&pool, &key(MAX_ENTRIES), later).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn clear_removes_everything() {
+        let pool = pool().await;
+        for audio in [b"one".as_slice(), b"two".as_slice()] {
+            let key = CacheKey::new(audio, "whisper-1@url", "auto");
+            store_in(&pool, &key, &response("text"), NOW).await;
+        }
+        assert_eq!(clear(&pool).await.unwrap(), 2);
+        assert_eq!(count(&pool).await, 0);
+    }
+}
@@ -0,0 +1,129 @@
+// The original detector: a hop is speech when its RMS or peak crosses a threshold. The
+// thresholds are fixed, or in adaptive mode set to a margin above the tracked noise floor,
+// so they drop in quiet rooms and rise in loud ones.
+use super::VoiceDetector;
+
+// Lowest adaptive RMS threshold, the default noise gate level: below it a near-silent
+// room's hiss would count as speech
+const MIN_ADAPTIVE_RMS: f32 = 0.003;
+
+pub struct EnergyVad {
+    sensitivity_rms: f32,
+    peak_threshold: f32,
+    // Linear gain over the noise floor, when adaptive
+    margin: Option<f32>,
+    noise_floor: Option<f32>,
+}
+
+impl EnergyVad {
+    pub fn new(sensitivity_rms: f32, peak_threshold: f32) -> Self {
+        Self {
+            sensitivity_rms,
+            peak_threshold,
+            margin: None,
+            noise_floor: None,
+        }
+    }
+
+    // Follow the noise floor, `margin_db` above it and never below `MIN_ADAPTIVE_RMS`. The
+    // fixed thresholds only apply until the floor is known; the peak threshold keeps their
+    // peak-to-RMS ratio.
+    pub fn adaptive(mut self, margin_db: f32) -> Self {
+        self.margin = Some(10f32.powf(margin_db / 20.0));
+        self
+    }
+
+    fn thresholds(&self) -> (f32, f32) {
+        match self.margin.zip(self.noise_floor) {
+            Some((margin, floor)) => {
+                let rms = (floor * margin).max(MIN_ADAPTIVE_RMS);
+                let peak_ratio = self.peak_threshold / self.sensitivity_rms.max(f32::EPSILON);
+                (rms, rms * peak_ratio)
+            }
+            None => (self.sensitivity_rms, self.peak_threshold),
+        }
+    }
+}
+
+impl VoiceDetector for EnergyVad {
+    fn is_speech(&mut self, hop: &[f32]) -> bool {
+        let (rms, peak) = audio_metrics(hop);
+        let (rms_threshold, peak_threshold) = self.thresholds();
+        rms > rms_threshold || peak > peak_threshold
+    }
+
+    fn set_noise_floor(&mut self, floor_rms: f32) {
+        self.noise_floor = Some(floor_rms);
+    }
+
+    fn threshold_rms(&self) -> Option<f32> {
+        Some(self.thresholds().0)
+    }
+}
+
+// Soft-knee noise gate applied to every hop before detection
+pub fn apply_noise_gate(samples: &[f32], threshold: f32) -> Vec<f32> {
+    const KNEE_RATIO: f32 = 3.0; // Compression ratio for soft knee
+
+    samples
+        .iter()
+        .map(|&s| {
+            let abs = s.abs();
+            if abs < threshold {
+                s * (abs / threshold).powf(1.0 / KNEE_RATIO)
+            } else {
+                s
+            }
+        })
+        .collect()
+}
+
+// Calculate RMS and peak (optimized)
+pub fn audio_metrics(chunk: &[f32]) -> (f32, f32) {
+    let mut sumsq = 0.0f32;
+    let mut peak = 0.0f32;
+
+    for &v in chunk {
+        let a = v.abs();
+        peak = peak.max(a);
+        sumsq += v * v;
+    }
+
+    let rms = (sumsq / chunk.len() as f32).sqrt();
+    (rms, peak)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adaptive_thresholds_follow_the_floor_both_ways() {
+        let mut vad = EnergyVad::new(0.012, 0.036).adaptive(6.0);
+        // Fixed thresholds until the floor is known
+        assert_eq!(vad.thresholds(), (0.012, 0.036));
+
+        // A quiet room lowers them, a loud one raises them
+        vad.set_noise_floor(0.004);
+        let (rms, peak) = vad.thresholds();
+        assert!((rms - 0.004 * 1.995).abs() < 1e-4);
+        assert!((peak - rms * 3.0).abs() < 1e-4);
+        vad.set_noise_floor(0.05);
+        assert!(vad.thresholds().0 > 0.09);
+
+        // Never below the minimum, however quiet
+        vad.set_noise_floor(0.0001);
+        assert_eq!(vad.thresholds().0, MIN_ADAPTIVE_RMS);
+    }
+
+    #[test]
+    fn quiet_speech_is_caught_once_the_floor_is_low() {
+        let hop = vec![0.008; 512];
+        let mut fixed = EnergyVad::new(0.012, 0.035);
+        let mut adaptive = EnergyVad::new(0.012, 0.035).adaptive(3.0);
+        fixed.set_noise_floor(0.003);
+        adaptive.set_noise_floor(0.003);
+        assert!(!fixed.is_speech(&hop));
+        assert!(adaptive.is_speech(&hop));
+    }
+}
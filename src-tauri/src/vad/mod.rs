@@ -0,0 +1,41 @@
+// Voice activity detectors for captured audio. Each one classifies a single hop of
+// noise-gated mono samples; turning hops into utterances stays in the capture loop.
+mod energy;
+mod gmm;
+#[cfg(feature = "neural-vad")]
+mod neural;
+mod noise_floor;
+
+pub use energy::{apply_noise_gate, audio_metrics, EnergyVad};
+pub use gmm::GmmVad;
+#[cfg(feature = "neural-vad")]
+pub use neural::NeuralVad;
+pub use noise_floor::NoiseFloorTracker;
+
+use serde::{Deserialize, Serialize};
+
+pub trait VoiceDetector: Send {
+    // Whether the hop contains speech. Hops arrive in order, so detectors may keep state.
+    fn is_speech(&mut self, hop: &[f32]) -> bool;
+
+    // Latest ambient RMS estimate, given before each hop. Detectors with a fixed
+    // threshold can place it relative to the room; the others ignore it.
+    fn set_noise_floor(&mut self, _floor_rms: f32) {}
+
+    // The RMS a hop currently needs to count as speech, when the detector has one
+    fn threshold_rms(&self) -> Option<f32> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VadKind {
+    // RMS and peak against fixed thresholds
+    #[default]
+    Energy,
+    // Adaptive per-band speech/noise models, after the WebRTC VAD, gated on voicing
+    Gmm,
+    // Silero VAD ONNX model; needs the `neural-vad` build feature and a model file
+    Neural,
+}
@@ -0,0 +1,99 @@
+// Silero VAD (v5 ONNX model) on the CPU. The model takes 512-sample windows at 16 kHz
+// plus 64 samples of context and keeps a recurrent state between calls, so hops are
+// resampled and re-chunked here.
+use super::VoiceDetector;
+use crate::speaker::resample::{ResampleQuality, Resampler};
+use ort::session::Session;
+use ort::value::Tensor;
+use std::path::Path;
+
+const MODEL_RATE: u32 = 16_000;
+const WINDOW: usize = 512;
+const CONTEXT: usize = 64;
+const STATE_LEN: usize = 2 * 128;
+const SPEECH_PROBABILITY: f32 = 0.5;
+
+pub struct NeuralVad {
+    session: Session,
+    resampler: Option<Resampler>,
+    resampled: Vec<f32>,
+    pending: Vec<f32>,
+    context: Vec<f32>,
+    state: Vec<f32>,
+    last_decision: bool,
+}
+
+impl NeuralVad {
+    pub fn new(model_path: &Path, sample_rate: u32) -> Result<Self, String> {
+        let session = Session::builder()
+            .and_then(|builder| builder.with_intra_threads(1))
+            .and_then(|builder| builder.commit_from_file(model_path))
+            .map_err(|e| format!("Failed to load VAD model {}: {}", model_path.display(), e))?;
+
+        Ok(Self {
+            session,
+            resampler: (sample_rate != MODEL_RATE)
+                .then(|| Resampler::new(sample_rate, MODEL_RATE, ResampleQuality::Low)),
+            resampled: Vec::new(),
+            pending: Vec::new(),
+            context: vec![0.0; CONTEXT],
+            state: vec![0.0; STATE_LEN],
+            last_decision: false,
+        })
+    }
+
+    // Speech probability of the next window
+    fn infer(&mut self, window: &[f32]) -> ort::Result<f32> {
+        let mut input = Vec::with_capacity(CONTEXT + WINDOW);
+        input.extend_from_slice(&self.context);
+        input.extend_from_slice(window);
+        self.context.copy_from_slice(&window[WINDOW - CONTEXT..]);
+
+        let input = Tensor::from_array(([1usize, CONTEXT + WINDOW], input))?;
+        let state = Tensor::from_array(([2usize, 1, 128], self.state.clone()))?;
+        let sample_rate = Tensor::from_array(((), vec![MODEL_RATE as i64]))?;
+        let outputs = self.session.run(ort::inputs![
+            "input" => input,
+            "state" => state,
+            "sr" => sample_rate,
+        ])?;
+
+        let (_, probability) = outputs["output"].try_extract_tensor::<f32>()?;
+        let (_, next_state) = outputs["stateN"].try_extract_tensor::<f32>()?;
+        self.state.copy_from_slice(next_state);
+        Ok(probability.first().copied().unwrap_or_default())
+    }
+}
+
+impl VoiceDetector for NeuralVad {
+    fn is_speech(&mut self, hop: &[f32]) -> bool {
+        match self.resampler.as_mut() {
+            Some(resampler) => {
+                resampler.process(hop, &mut self.resampled);
+                self.pending.append(&mut self.resampled);
+            }
+            None => self.pending.extend_from_slice(hop),
+        }
+
+        // A hop shorter than a window keeps the previous decision
+        let mut decision = None;
+        while self.pending.len() >= WINDOW {
+            let window: Vec<f32> = self.pending.drain(..WINDOW).collect();
+            match self.infer(&window) {
+                Ok(probability) => {
+                    let speech = probability > SPEECH_PROBABILITY;
+                    decision = Some(decision.unwrap_or(false) || speech);
+                }
+                Err(e) => {
+                    tracing::warn!("VAD model inference failed: {}", e);
+                    decision.get_or_insert(false);
+                }
+            }
+        }
+
+        if let Some(decision) = decision {
+            self.last_decision = decision;
+        }
+        self.last_decision
+    }
+}
@@ -0,0 +1,144 @@
+// Minimum-statistics noise floor (after Martin, 2001): hop power is smoothed, and the
+// floor is the lowest smoothed value over the last couple of seconds. Pauses between
+// words reach down to the ambient level, so the estimate holds during speech and follows
+// the room when it gets louder or quieter.
+use std::collections::VecDeque;
+
+// Time constant of the power smoothing
+const SMOOTHING_SECS: f32 = 0.1;
+// The search window is split into sub-windows so old minima drop out in steps
+const SUBWINDOW_SECS: f32 = 0.25;
+const SUBWINDOWS: usize = 8;
+// The minimum of smoothed noise power sits below its mean
+const MINIMUM_BIAS: f32 = 1.5;
+
+pub struct NoiseFloorTracker {
+    sample_rate: u32,
+    smoothed: Option<f32>,
+    current_min: f32,
+    current_hops: usize,
+    minima: VecDeque<f32>,
+}
+
+impl NoiseFloorTracker {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            smoothed: None,
+            current_min: f32::INFINITY,
+            current_hops: 0,
+            minima: VecDeque::with_capacity(SUBWINDOWS),
+        }
+    }
+
+    pub fn update(&mut self, hop: &[f32]) {
+        if hop.is_empty() {
+            return;
+        }
+        let power = hop.iter().map(|s| s * s).sum::<f32>() / hop.len() as f32;
+        let hop_secs = hop.len() as f32 / self.sample_rate as f32;
+        let alpha = (-hop_secs / SMOOTHING_SECS).exp();
+        let smoothed = match self.smoothed {
+            Some(previous) => alpha * previous + (1.0 - alpha) * power,
+            None => power,
+        };
+        self.smoothed = Some(smoothed);
+
+        self.current_min = self.current_min.min(smoothed);
+        self.current_hops += 1;
+        let subwindow_hops = (SUBWINDOW_SECS / hop_secs).ceil().max(1.0) as usize;
+        if self.current_hops >= subwindow_hops {
+            if self.minima.len() == SUBWINDOWS {
+                self.minima.pop_front();
+            }
+            self.minima.push_back(self.current_min);
+            self.current_min = f32::INFINITY;
+            self.current_hops = 0;
+        }
+    }
+
+    // Ambient RMS level, once a full sub-window has been seen
+    pub fn floor_rms(&self) -> Option<f32> {
+        if self.minima.is_empty() {
+            return None;
+        }
+        let min_power = self
+            .minima
+            .iter()
+            .fold(self.current_min, |min, &power| min.min(power));
+        Some((min_power * MINIMUM_BIAS).sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 44_100;
+    const HOP: usize = 1024;
+
+    // Deterministic white noise at roughly the given RMS
+    struct Noise(u32);
+
+    impl Noise {
+        fn hop(&mut self, rms: f32) -> Vec<f32> {
+            (0..HOP)
+                .map(|_| {
+                    self.0 = self.0.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                    let uniform = (self.0 >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0;
+                    // Uniform noise on [-a, a] has an RMS of a / sqrt(3)
+                    uniform * rms * 3f32.sqrt()
+                })
+                .collect()
+        }
+    }
+
+    fn feed(tracker: &mut NoiseFloorTracker, noise: &mut Noise, rms: f32, secs: f32) {
+        let hops = (secs * RATE as f32 / HOP as f32) as usize;
+        for _ in 0..hops {
+            tracker.update(&noise.hop(rms));
+        }
+    }
+
+    fn assert_near(floor: Option<f32>, expected: f32) {
+        let floor = floor.expect("no noise floor yet");
+        assert!(
+            floor > expected * 0.5 && floor < expected * 1.5,
+            "floor {} is not near {}",
+            floor,
+            expected
+        );
+    }
+
+    #[test]
+    fn follows_a_step_in_ambient_level() {
+        let mut tracker = NoiseFloorTracker::new(RATE);
+        let mut noise = Noise(1);
+        assert!(tracker.floor_rms().is_none());
+
+        feed(&mut tracker, &mut noise, 0.01, 3.0);
+        assert_near(tracker.floor_rms(), 0.01);
+
+        // A fan turns on, then off again
+        feed(&mut tracker, &mut noise, 0.05, 3.0);
+        assert_near(tracker.floor_rms(), 0.05);
+        feed(&mut tracker, &mut noise, 0.01, 0.5);
+        assert_near(tracker.floor_rms(), 0.01);
+    }
+
+    #[test]
+    fn holds_during_speech() {
+        let mut tracker = NoiseFloorTracker::new(RATE);
+        let mut noise = Noise(2);
+        feed(&mut tracker, &mut noise, 0.01, 3.0);
+
+        // A long sentence, then sentences with pauses between them
+        feed(&mut tracker, &mut noise, 0.2, 1.5);
+        assert_near(tracker.floor_rms(), 0.01);
+        for _ in 0..6 {
+            feed(&mut tracker, &mut noise, 0.01, 0.8);
+            feed(&mut tracker, &mut noise, 0.2, 1.2);
+            assert_near(tracker.floor_rms(), 0.01);
+        }
+    }
+}
@@ -0,0 +1,314 @@
+// User vocabulary (product names, people, acronyms) used to bias transcription and to
+// correct near-miss spellings in the returned text
+use crate::db::get_pool;
+use serde::Serialize;
+use tauri::AppHandle;
+
+const MAX_TERM_CHARS: usize = 100;
+// Whisper only reads the last ~224 tokens of the prompt
+const MAX_PROMPT_CHARS: usize = 800;
+// Shorter terms are too close to ordinary words ("Rust" and "just") to allow any edit
+const MIN_FUZZY_CHARS: usize = 6;
+
+// Words that are never fuzzy-matched to a term, however close: a transcript saying
+// "next is" means it
+const COMMON_WORDS: &[&str] = &[
+    "a", "about", "after", "again", "all", "also", "am", "an", "and", "any", "are", "as", "at",
+    "back", "be", "because", "been", "before", "being", "but", "by", "came", "can", "come",
+    "could", "day", "did", "do", "does", "done", "down", "each", "even", "every", "first", "for",
+    "from", "get", "give", "go", "going", "good", "got", "had", "has", "have", "he", "her", "here",
+    "him", "his", "how", "i", "if", "in", "into", "is", "it", "its", "just", "know", "last", "let",
+    "like", "look", "made", "make", "many", "may", "me", "more", "most", "much", "must", "my",
+    "need", "never", "new", "next", "no", "not", "now", "of", "off", "on", "one", "only", "or",
+    "other", "our", "out", "over", "people", "place", "put", "really", "right", "said", "same",
+    "say", "see", "she", "should", "since", "so", "some", "still", "such", "take", "than", "that",
+    "the", "their", "them", "then", "there", "these", "they", "thing", "things", "think", "this",
+    "those", "time", "to", "too", "two", "under", "up", "us", "use", "used", "very", "want", "was",
+    "way", "we", "well", "were", "what", "when", "where", "which", "while", "who", "why", "will",
+    "with", "work", "would", "year", "yes", "yet", "you", "your",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VocabularyTerm {
+    pub id: i64,
+    pub term: String,
+    pub created_at: String,
+}
+
+// Terms in insertion order; empty when the database is unavailable
+pub async fn load_vocabulary(app: &AppHandle) -> Vec<String> {
+    let Ok(pool) = get_pool(app).await else {
+        return Vec::new();
+    };
+
+    sqlx::query_scalar::<_, String>("SELECT term FROM vocabulary ORDER BY id ASC")
+        .fetch_all(&pool)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load vocabulary: {}", e);
+            Vec::new()
+        })
+}
+
+// Prompt sent with the transcription request; the provider spells listed terms the same way
+pub fn build_vocabulary_prompt(terms: &[String]) -> Option<String> {
+    let mut prompt = String::new();
+    for term in terms {
+        if prompt.len() + term.len() + 2 > MAX_PROMPT_CHARS {
+            break;
+        }
+        if !prompt.is_empty() {
+            prompt.push_str(", ");
+        }
+        prompt.push_str(term);
+    }
+
+    (!prompt.is_empty()).then(|| format!("Vocabulary: {}.", prompt))
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+// Lowercase letters and digits only, so "Kuber-netes" and "kubernetes" compare equal
+fn comparison_key(text: &str) -> Vec<char> {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+// Edits tolerated for a term of this length
+fn max_distance(key_len: usize) -> usize {
+    if key_len < MIN_FUZZY_CHARS {
+        return 0;
+    }
+    match key_len {
+        0..=9 => 1,
+        10..=13 => 2,
+        _ => 3,
+    }
+}
+
+fn is_common_word(word: &[char]) -> bool {
+    let word: String = word.iter().collect();
+    COMMON_WORDS.contains(&word.as_str())
+}
+
+// Byte spans of the words in `text`, without surrounding punctuation
+fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+
+    for chunk in text.split_inclusive(char::is_whitespace) {
+        let word = chunk.trim_end();
+        let trimmed_start = word.trim_start_matches(|c: char| !c.is_alphanumeric());
+        let trimmed = trimmed_start.trim_end_matches(|c: char| !c.is_alphanumeric());
+        if !trimmed.is_empty() {
+            let start = offset + (word.len() - trimmed_start.len());
+            spans.push((start, start + trimmed.len()));
+        }
+        offset += chunk.len();
+    }
+
+    spans
+}
+
+struct Candidate {
+    term_index: usize,
+    words: usize,
+    distance: usize,
+}
+
+// Replaces words (or runs of words) that are within a few edits of a vocabulary term.
+// A term may also match one more word than it has, since splits like "postgres sql"
+// for "PostgreSQL" are common.
+pub fn correct_transcript(text: &str, terms: &[String]) -> String {
+    let terms: Vec<(&String, Vec<char>, usize)> = terms
+        .iter()
+        .map(|term| (term, comparison_key(term), term.split_whitespace().count()))
+        .filter(|(_, key, words)| !key.is_empty() && *words > 0)
+        .collect();
+    if terms.is_empty() {
+        return text.to_string();
+    }
+
+    let spans = word_spans(text);
+    let mut corrected = String::with_capacity(text.len());
+    let mut copied_until = 0;
+    let mut i = 0;
+
+    while i < spans.len() {
+        let mut best: Option<Candidate> = None;
+
+        for (term_index, (_, term_key, term_words)) in terms.iter().enumerate() {
+            for words in [*term_words, term_words + 1] {
+                let Some(window) = spans.get(i..i + words) else {
+                    continue;
+                };
+                // Only join words separated by whitespace, never across punctuation
+                let joined_cleanly = window
+                    .windows(2)
+                    .all(|pair| text[pair[0].1..pair[1].0].trim().is_empty());
+                if !joined_cleanly {
+                    continue;
+                }
+
+                let window_text = &text[window[0].0..window[words - 1].1];
+                let window_key = comparison_key(window_text);
+                let allowed = max_distance(term_key.len());
+                if window_key.len().abs_diff(term_key.len()) > allowed {
+                    continue;
+                }
+                let distance = levenshtein(&window_key, term_key);
+                if distance > 0
+                    && window
+                        .iter()
+                        .any(|&(start, end)| is_common_word(&comparison_key(&text[start..end])))
+                {
+                    continue;
+                }
+                // Recapitalising a short term would hit every ordinary use of the word
+                // ("go" for "Go"); only spelling fixes like "next js" are applied
+                if term_key.len() < MIN_FUZZY_CHARS
+                    && window_text.to_lowercase() == terms[term_index].0.to_lowercase()
+                {
+                    continue;
+                }
+                let better = match &best {
+                    Some(best) => {
+                        distance < best.distance
+                            || (distance == best.distance && words > best.words)
+                    }
+                    None => true,
+                };
+                if distance <= allowed && better {
+                    best = Some(Candidate {
+                        term_index,
+                        words,
+                        distance,
+                    });
+                }
+            }
+        }
+
+        match best {
+            Some(candidate) => {
+                let (start, end) = (spans[i].0, spans[i + candidate.words - 1].1);
+                corrected.push_str(&text[copied_until..start]);
+                corrected.push_str(terms[candidate.term_index].0);
+                copied_until = end;
+                i += candidate.words;
+            }
+            None => i += 1,
+        }
+    }
+
+    corrected.push_str(&text[copied_until..]);
+    corrected
+}
+
+#[tauri::command]
+pub async fn get_vocabulary(app: AppHandle) -> Result<Vec<VocabularyTerm>, String> {
+    let pool = get_pool(&app).await?;
+    let rows: Vec<(i64, String, String)> =
+        sqlx::query_as("SELECT id, term, created_at FROM vocabulary ORDER BY id ASC")
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| format!("Failed to read vocabulary: {}", e))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, term, created_at)| VocabularyTerm {
+            id,
+            term,
+            created_at,
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn add_vocabulary_term(app: AppHandle, term: String) -> Result<(), String> {
+    let term = term.split_whitespace().collect::<Vec<_>>().join(" ");
+    if term.is_empty() {
+        return Err("Vocabulary term must not be empty".to_string());
+    }
+    if term.chars().count() > MAX_TERM_CHARS {
+        return Err(format!(
+            "Vocabulary term must be at most {} characters",
+            MAX_TERM_CHARS
+        ));
+    }
+
+    let pool = get_pool(&app).await?;
+    sqlx::query("INSERT OR IGNORE INTO vocabulary (term) VALUES (?)")
+        .bind(&term)
+        .execute(&pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to add vocabulary term: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_vocabulary_term(app: AppHandle, id: i64) -> Result<(), String> {
+    let pool = get_pool(&app).await?;
+    sqlx::query("DELETE FROM vocabulary WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to delete vocabulary term: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn correct(text: &str, terms: &[&str]) -> String {
+        let terms: Vec<String> = terms.iter().map(|term| term.to_string()).collect();
+        correct_transcript(text, &terms)
+    }
+
+    #[test]
+    fn fixes_near_misses_of_long_terms() {
+        assert_eq!(
+            correct("we moved to kuberentes", &["Kubernetes"]),
+            "we moved to Kubernetes"
+        );
+        assert_eq!(
+            correct("it runs on postgres sql.", &["PostgreSQL"]),
+            "it runs on PostgreSQL."
+        );
+        assert_eq!(
+            correct("built with next js", &["Next.js"]),
+            "built with Next.js"
+        );
+    }
+
+    #[test]
+    fn leaves_ordinary_words_alone() {
+        assert_eq!(correct("I just must rest", &["Rust"]), "I just must rest");
+        assert_eq!(correct("a black cat", &["Slack"]), "a black cat");
+        assert_eq!(correct("let's go", &["Go"]), "let's go");
+        assert_eq!(
+            correct("we wrote it in rust", &["Rust"]),
+            "we wrote it in rust"
+        );
+        assert_eq!(
+            correct("what comes next is the demo", &["Next.js"]),
+            "what comes next is the demo"
+        );
+    }
+}
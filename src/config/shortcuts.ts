@@ -71,4 +71,15 @@ export const DEFAULT_SHORTCUT_ACTIONS: ShortcutAction[] = [
       linux: "ctrl+shift+s",
     },
   },
+  {
+    id: "insert_answer",
+    name: "Insert Answer",
+    description:
+      "Paste or type the last answer into the focused app (press again to stop typing)",
+    defaultKey: {
+      macos: "cmd+shift+y",
+      windows: "ctrl+shift+y",
+      linux: "ctrl+shift+y",
+    },
+  },
 ];
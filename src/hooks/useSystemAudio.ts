@@ -31,6 +31,71 @@ export interface VadConfig {
   pre_speech_chunks: number;
   noise_gate_threshold: number;
   max_recording_duration_secs: number;
+  // null keeps the device rate; e.g. 16000 for speech models
+  output_sample_rate?: number | null;
+  resample_quality?: ResampleQuality;
+  detector?: VadKind;
+  // Silero VAD .onnx file, required by the "neural" detector
+  neural_model_path?: string | null;
+  // Energy detector: set the thresholds relative to the room's noise floor
+  adaptive_threshold?: boolean;
+  threshold_margin_db?: number;
+}
+
+export type ResampleQuality = "low" | "medium" | "high";
+export type VadKind = "energy" | "gmm" | "neural";
+
+// "me" is the microphone, "them" is system audio
+export type CaptureSource = "me" | "them";
+
+// `speech-detected` payload matching Rust
+export interface SpeechDetectedPayload {
+  audio: string;
+  id: string;
+  source: CaptureSource;
+  // From the start of the first running capture
+  timeline_ms: number;
+  // Wall-clock Unix milliseconds
+  started_at: number;
+  ended_at: number;
+  duration_ms: number;
+  sample_rate: number;
+  // Levels before normalization
+  peak: number;
+  rms: number;
+  // Hit the length cap or a pause instead of ending in silence
+  force_cut: boolean;
+  pre_speech_ms: number;
+}
+
+// `audio-level` payload matching Rust, about 20 per second while capturing
+export interface AudioLevelPayload {
+  source: CaptureSource;
+  // Levels before the noise gate
+  rms: number;
+  peak: number;
+  // VAD decision; null in continuous mode
+  speech: boolean | null;
+  gate_open: boolean;
+}
+
+export type DiscardReason = "too_short" | "stream_ended";
+
+// `speech-discarded` payload matching Rust
+export interface SpeechDiscardedPayload {
+  source: CaptureSource;
+  reason: DiscardReason;
+  timeline_ms: number;
+  duration_ms: number;
+  speech_ms: number;
+}
+
+// `noise-floor` payload matching Rust (RMS levels, floor also in dBFS)
+export interface NoiseFloorPayload {
+  source: CaptureSource;
+  floor_rms: number;
+  floor_db: number;
+  threshold_rms: number | null;
 }
 
 // OPTIMIZED VAD defaults - matches backend exactly for perfect performance
@@ -44,6 +109,12 @@ const DEFAULT_VAD_CONFIG: VadConfig = {
   pre_speech_chunks: 12, // ~0.27s - enough to catch word start
   noise_gate_threshold: 0.003, // Stronger noise filtering
   max_recording_duration_secs: 180, // 3 minutes default
+  output_sample_rate: null, // Device rate
+  resample_quality: "medium",
+  detector: "energy",
+  neural_model_path: null,
+  adaptive_threshold: true,
+  threshold_margin_db: 3, // Speech must be 3 dB above the room
 };
 
 // Chat message interface (reusing from useCompletion)
@@ -70,6 +141,8 @@ export function useSystemAudio() {
   const globalShortcuts = useGlobalShortcuts();
   const [isPopoverOpen, setIsPopoverOpen] = useState(false);
   const [capturing, setCapturing] = useState(false);
+  // System audio stays open while paused; no utterances are produced
+  const [paused, setPaused] = useState(false);
   const [isProcessing, setIsProcessing] = useState(false);
   const [isAIProcessing, setIsAIProcessing] = useState(false);
   const [lastTranscription, setLastTranscription] = useState<string>("");
@@ -81,6 +154,8 @@ export function useSystemAudio() {
     useState<boolean>(false);
   const [showQuickActions, setShowQuickActions] = useState<boolean>(true);
   const [vadConfig, setVadConfig] = useState<VadConfig>(DEFAULT_VAD_CONFIG);
+  const [noiseFloor, setNoiseFloor] = useState<NoiseFloorPayload | null>(null);
+  const [audioLevel, setAudioLevel] = useState<AudioLevelPayload | null>(null);
   const [recordingProgress, setRecordingProgress] = useState<number>(0); // For continuous mode
   const [isContinuousMode, setIsContinuousMode] = useState<boolean>(false);
   const [isRecordingInContinuousMode, setIsRecordingInContinuousMode] =
@@ -165,6 +240,10 @@ export function useSystemAudio() {
     let stopUnlisten: (() => void) | undefined;
     let errorUnlisten: (() => void) | undefined;
     let discardedUnlisten: (() => void) | undefined;
+    let noiseFloorUnlisten: (() => void) | undefined;
+    let levelUnlisten: (() => void) | undefined;
+    let pausedUnlisten: (() => void) | undefined;
+    let resumedUnlisten: (() => void) | undefined;
 
     const setupContinuousListeners = async () => {
       try {
@@ -196,12 +275,46 @@ export function useSystemAudio() {
           setIsRecordingInContinuousMode(false);
         });
 
-        // Speech discarded (too short)
+        // Speech discarded (too short, or the stream ended mid-utterance)
         discardedUnlisten = await listen("speech-discarded", (event) => {
-          const reason = event.payload as string;
-          console.log("Speech discarded:", reason);
+          const discarded = event.payload as SpeechDiscardedPayload;
+          console.log("Speech discarded:", discarded.reason, discarded);
           // Don't show error - this is expected behavior
         });
+
+        // Ambient level and the speech threshold derived from it (about 1 Hz)
+        noiseFloorUnlisten = await listen<NoiseFloorPayload>(
+          "noise-floor",
+          (event) => {
+            if (event.payload.source === "them") {
+              setNoiseFloor(event.payload);
+            }
+          }
+        );
+
+        // Pause state, also when changed by another caller
+        pausedUnlisten = await listen<CaptureSource>(
+          "capture-paused",
+          (event) => {
+            if (event.payload === "them") setPaused(true);
+          }
+        );
+        resumedUnlisten = await listen<CaptureSource>(
+          "capture-resumed",
+          (event) => {
+            if (event.payload === "them") setPaused(false);
+          }
+        );
+
+        // Live input level meter
+        levelUnlisten = await listen<AudioLevelPayload>(
+          "audio-level",
+          (event) => {
+            if (event.payload.source === "them") {
+              setAudioLevel(event.payload);
+            }
+          }
+        );
       } catch (err) {
         console.error("Failed to setup continuous recording listeners:", err);
       }
@@ -215,6 +328,10 @@ export function useSystemAudio() {
       if (stopUnlisten) stopUnlisten();
       if (errorUnlisten) errorUnlisten();
       if (discardedUnlisten) discardedUnlisten();
+      if (noiseFloorUnlisten) noiseFloorUnlisten();
+      if (levelUnlisten) levelUnlisten();
+      if (pausedUnlisten) pausedUnlisten();
+      if (resumedUnlisten) resumedUnlisten();
     };
   }, []);
 
@@ -228,7 +345,10 @@ export function useSystemAudio() {
           try {
             if (!capturing) return;
 
-            const base64Audio = event.payload as string;
+            const { audio: base64Audio, source } =
+              event.payload as SpeechDetectedPayload;
+            // Only the other party's speech is answered here
+            if (source !== "them") return;
             // Convert to blob
             const binaryString = atob(base64Audio);
             const bytes = new Uint8Array(binaryString.length);
@@ -259,6 +379,7 @@ export function useSystemAudio() {
               provider: providerConfig,
               selectedProvider: selectedSttProvider,
               audio: audioBlob,
+              source: "system_audio",
             });
 
             const timeoutPromise = new Promise<string>((_, reject) => {
@@ -600,6 +721,8 @@ export function useSystemAudio() {
       setIsContinuousMode(false);
       setIsRecordingInContinuousMode(false);
       setRecordingProgress(0);
+      setAudioLevel(null);
+      setPaused(false);
       setLastTranscription("");
       setLastAIResponse("");
       setError("");
@@ -788,6 +911,20 @@ export function useSystemAudio() {
     setUseSystemPrompt(true);
   }, []);
 
+  // Pause/resume system audio without closing the device
+  const togglePause = useCallback(async () => {
+    try {
+      await invoke(paused ? "resume_capture" : "pause_capture", {
+        source: "them",
+      });
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : String(err);
+      setError(
+        `Failed to ${paused ? "resume" : "pause"} capture: ${errorMessage}`
+      );
+    }
+  }, [paused]);
+
   // Update VAD configuration
   const updateVadConfiguration = useCallback(async (config: VadConfig) => {
     try {
@@ -895,6 +1032,8 @@ export function useSystemAudio() {
     setupRequired,
     startCapture,
     stopCapture,
+    paused,
+    togglePause,
     handleSetup,
     isPopoverOpen,
     setIsPopoverOpen,
@@ -922,6 +1061,8 @@ export function useSystemAudio() {
     // VAD configuration
     vadConfig,
     updateVadConfiguration,
+    noiseFloor,
+    audioLevel,
     // Continuous recording
     isContinuousMode,
     isRecordingInContinuousMode,
@@ -11,7 +11,10 @@ import curl2Json from "@bany/curl-to-json";
 import { shouldUsePluelyAPI } from "./pluely.api";
 
 // Pluely STT function
-async function fetchPluelySTT(audio: File | Blob): Promise<string> {
+async function fetchPluelySTT(
+  audio: File | Blob,
+  source?: STTSource
+): Promise<string> {
   try {
     // Convert audio to base64
     const audioBase64 = await blobToBase64(audio);
@@ -21,8 +24,10 @@ async function fetchPluelySTT(audio: File | Blob): Promise<string> {
       success: boolean;
       transcription?: string;
       error?: string;
+      language?: string;
     }>("transcribe_audio", {
       audioBase64,
+      source,
     });
 
     if (response.success && response.transcription) {
@@ -36,6 +41,9 @@ async function fetchPluelySTT(audio: File | Blob): Promise<string> {
   }
 }
 
+// Where the audio came from; system audio enables backend question detection
+export type STTSource = "microphone" | "system_audio";
+
 export interface STTParams {
   provider: TYPE_PROVIDER | undefined;
   selectedProvider: {
@@ -43,6 +51,7 @@ export interface STTParams {
     variables: Record<string, string>;
   };
   audio: File | Blob;
+  source?: STTSource;
 }
 
 /**
@@ -52,12 +61,12 @@ export async function fetchSTT(params: STTParams): Promise<string> {
   let warnings: string[] = [];
 
   try {
-    const { provider, selectedProvider, audio } = params;
+    const { provider, selectedProvider, audio, source } = params;
 
     // Check if we should use Pluely API instead
     const usePluelyAPI = await shouldUsePluelyAPI();
     if (usePluelyAPI) {
-      return await fetchPluelySTT(audio);
+      return await fetchPluelySTT(audio, source);
     }
 
     if (!provider) throw new Error("Provider not provided");
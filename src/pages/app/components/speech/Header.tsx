@@ -1,4 +1,4 @@
-import { X } from "lucide-react";
+import { PauseIcon, PlayIcon, X } from "lucide-react";
 import { Button } from "@/components";
 
 type Props = {
@@ -6,6 +6,8 @@ type Props = {
   setIsPopoverOpen: React.Dispatch<React.SetStateAction<boolean>>;
   resizeWindow: (expanded: boolean) => Promise<void>;
   capturing: boolean;
+  paused: boolean;
+  togglePause: () => Promise<void>;
 };
 
 export const Header = ({
@@ -13,6 +15,8 @@ export const Header = ({
   setIsPopoverOpen,
   resizeWindow,
   capturing,
+  paused,
+  togglePause,
 }: Props) => {
   return (
     <div className="flex flex-col gap-3">
@@ -22,10 +26,24 @@ export const Header = ({
           <p className="text-xs text-muted-foreground mt-1">
             {setupRequired
               ? "Setup required to capture system audio"
+              : paused
+              ? "Paused - audio stays connected, nothing is sent"
               : "Until and unless sound is detected from your speakers no api calls will be made"}
           </p>
         </div>
-        {!capturing ? (
+        {capturing ? (
+          <Button
+            size="icon"
+            title={paused ? "Resume capture" : "Pause capture"}
+            onClick={togglePause}
+          >
+            {paused ? (
+              <PlayIcon className="h-4 w-4" />
+            ) : (
+              <PauseIcon className="h-4 w-4" />
+            )}
+          </Button>
+        ) : (
           <div className="">
             <Button
               size="icon"
@@ -38,7 +56,7 @@ export const Header = ({
               <X className="h-4 w-4" />
             </Button>
           </div>
-        ) : null}
+        )}
       </div>
     </div>
   );
@@ -1,19 +1,32 @@
 import { useState } from "react";
 import { Button, Card, Label, Slider, Switch } from "@/components";
 import { ArrowDownIcon, ArrowUpIcon, SettingsIcon } from "lucide-react";
-import { VadConfig } from "@/hooks/useSystemAudio";
+import {
+  AudioLevelPayload,
+  NoiseFloorPayload,
+  VadConfig,
+} from "@/hooks/useSystemAudio";
 
 interface VadConfigPanelProps {
   vadConfig: VadConfig;
   onUpdate: (config: VadConfig) => void;
+  noiseFloor: NoiseFloorPayload | null;
+  audioLevel: AudioLevelPayload | null;
 }
 
+// Meter width for an RMS level, spanning -60 to 0 dBFS
+const levelPercent = (rms: number) =>
+  Math.min(100, Math.max(0, ((20 * Math.log10(rms + 1e-9) + 60) / 60) * 100));
+
 export const VadConfigPanel = ({
   vadConfig,
   onUpdate,
+  noiseFloor,
+  audioLevel,
 }: VadConfigPanelProps) => {
   const [isOpen, setIsOpen] = useState(false);
   const [localConfig, setLocalConfig] = useState(vadConfig);
+  const adaptive = localConfig.adaptive_threshold ?? true;
 
   const handleUpdate = (updates: Partial<VadConfig>) => {
     const newConfig = { ...localConfig, ...updates };
@@ -49,6 +62,24 @@ export const VadConfigPanel = ({
         </Button>
       </div>
 
+      {/* Input level meter */}
+      {audioLevel && (
+        <div className="px-2" title="System audio input level">
+          <div className="w-full bg-muted rounded-full h-1.5 overflow-hidden">
+            <div
+              className={`h-1.5 rounded-full transition-all duration-75 ${
+                audioLevel.speech
+                  ? "bg-green-500"
+                  : audioLevel.gate_open
+                  ? "bg-primary"
+                  : "bg-muted-foreground/40"
+              }`}
+              style={{ width: `${levelPercent(audioLevel.rms)}%` }}
+            />
+          </div>
+        </div>
+      )}
+
       {isOpen && (
         <Card className="p-4 space-y-4 bg-muted/30">
           {/* VAD Enable/Disable */}
@@ -71,7 +102,56 @@ export const VadConfigPanel = ({
 
           {localConfig.enabled ? (
             <>
-              {/* Sensitivity */}
+              {/* Adaptive threshold */}
+              <div className="flex items-center justify-between gap-4">
+                <div>
+                  <Label className="text-xs font-medium">
+                    Adapt to Room Noise
+                  </Label>
+                  <p className="text-xs text-muted-foreground mt-1">
+                    {noiseFloor
+                      ? `Noise floor ${noiseFloor.floor_db.toFixed(0)} dB${
+                          noiseFloor.threshold_rms !== null
+                            ? `, speech above ${(
+                                noiseFloor.threshold_rms * 1000
+                              ).toFixed(1)}`
+                            : ""
+                        }`
+                      : "Sets the speech threshold from the room's noise level"}
+                  </p>
+                </div>
+                <Switch
+                  checked={adaptive}
+                  onCheckedChange={(adaptive_threshold) =>
+                    handleUpdate({ adaptive_threshold })
+                  }
+                />
+              </div>
+
+              {adaptive && (
+                <div className="space-y-2">
+                  <Label className="text-xs font-medium flex items-center justify-between">
+                    <span>Margin Above Noise</span>
+                    <span className="text-muted-foreground font-normal">
+                      {localConfig.threshold_margin_db ?? 3} dB
+                    </span>
+                  </Label>
+                  <Slider
+                    value={[localConfig.threshold_margin_db ?? 3]}
+                    onValueChange={([value]) =>
+                      handleUpdate({ threshold_margin_db: value })
+                    }
+                    min={1}
+                    max={20}
+                    step={1}
+                    className="w-full"
+                  />
+                  <p className="text-xs text-muted-foreground">
+                    Lower = more sensitive (picks up quieter speech)
+                  </p>
+                </div>
+              )}
+
               <div className="space-y-2">
                 <Label className="text-xs font-medium flex items-center justify-between">
                   <span>Speech Sensitivity</span>
@@ -213,6 +293,8 @@ export const VadConfigPanel = ({
                   pre_speech_chunks: 12,
                   noise_gate_threshold: 0.003,
                   max_recording_duration_secs: 180, // 3 minutes
+                  adaptive_threshold: true,
+                  threshold_margin_db: 3,
                 };
                 setLocalConfig(defaultConfig);
                 onUpdate(defaultConfig);
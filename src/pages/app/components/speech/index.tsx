@@ -10,6 +10,7 @@ import {
   AlertCircleIcon,
   LoaderIcon,
   AudioLinesIcon,
+  PauseIcon,
 } from "lucide-react";
 import { Warning } from "./Warning";
 import { Header } from "./Header";
@@ -31,6 +32,8 @@ export const SystemAudio = (props: useSystemAudioType) => {
     setupRequired,
     startCapture,
     stopCapture,
+    paused,
+    togglePause,
     isPopoverOpen,
     setIsPopoverOpen,
     useSystemPrompt,
@@ -51,6 +54,8 @@ export const SystemAudio = (props: useSystemAudioType) => {
     handleQuickActionClick,
     vadConfig,
     updateVadConfiguration,
+    noiseFloor,
+    audioLevel,
     isContinuousMode,
     isRecordingInContinuousMode,
     recordingProgress,
@@ -73,6 +78,7 @@ export const SystemAudio = (props: useSystemAudioType) => {
     if (error && !setupRequired)
       return <AlertCircleIcon className="text-red-500" />;
     if (isProcessing) return <LoaderIcon className="animate-spin" />;
+    if (capturing && paused) return <PauseIcon className="text-green-500" />;
     if (capturing)
       return <AudioLinesIcon className="text-green-500 animate-pulse" />;
     return <HeadphonesIcon />;
@@ -82,6 +88,7 @@ export const SystemAudio = (props: useSystemAudioType) => {
     if (setupRequired) return "Setup required - Click for instructions";
     if (error && !setupRequired) return `Error: ${error}`;
     if (isProcessing) return "Transcribing audio...";
+    if (capturing && paused) return "Capture paused - Click to stop";
     if (capturing) return "Stop system audio capture";
     return "Start system audio capture";
   };
@@ -132,6 +139,8 @@ export const SystemAudio = (props: useSystemAudioType) => {
                   setIsPopoverOpen={setIsPopoverOpen}
                   resizeWindow={resizeWindow}
                   capturing={capturing}
+                  paused={paused}
+                  togglePause={togglePause}
                 />
               )}
 
@@ -293,6 +302,8 @@ export const SystemAudio = (props: useSystemAudioType) => {
                   <VadConfigPanel
                     vadConfig={vadConfig}
                     onUpdate={updateVadConfiguration}
+                    noiseFloor={noiseFloor}
+                    audioLevel={audioLevel}
                   />
                 </>
               )}